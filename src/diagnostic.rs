@@ -0,0 +1,100 @@
+use std::fmt::{Display, Formatter};
+use crate::proposition::Span;
+
+/// A problem found in some source text, with enough context to show the user where it is
+/// and what could have appeared there instead.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    /// The full text which was being read.
+    pub source: String,
+    /// Where in source the problem is.
+    pub span: Span,
+    /// A short description of the problem.
+    pub message: String,
+    /// Descriptions of the tokens that would have been accepted at span.
+    pub expected: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(source: &str, span: Span, message: &str, expected: &[&str]) -> Diagnostic {
+        Diagnostic {
+            source: source.to_string(),
+            span,
+            message: message.to_string(),
+            expected: expected.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Move self from a substring of source starting at offset to source itself.
+    pub fn offset(self, source: &str, offset: usize) -> Diagnostic {
+        Diagnostic {
+            source: source.to_string(),
+            span: Span::new(self.span.start + offset, self.span.end + offset),
+            ..self
+        }
+    }
+
+    /// Return the line of source containing the start of span, with span underlined by carets.
+    ///
+    /// ```text
+    /// error: unexpected `)` at 3..4
+    ///   |
+    /// 1 | A B)
+    ///   |    ^
+    ///   = expected one of: a binary connective, end of input
+    /// ```
+    pub fn render(&self) -> String {
+        let line_start = self.source[..self.span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[self.span.start..].find('\n').map_or(self.source.len(), |i| self.span.start + i);
+        let line_number = self.source[..line_start].matches('\n').count() + 1;
+        let line = &self.source[line_start..line_end];
+
+        let column = self.source[line_start..self.span.start].chars().count();
+        let width = self.source[self.span.start..self.span.end.min(line_end)].chars().count().max(1);
+        let gutter = " ".repeat(line_number.to_string().len());
+
+        let mut rendered = format!("error: {} at {}\n", self.message, self.span);
+        rendered.push_str(&format!("{gutter} |\n"));
+        rendered.push_str(&format!("{line_number} | {line}\n"));
+        rendered.push_str(&format!("{gutter} | {}{}", " ".repeat(column), "^".repeat(width)));
+        match self.expected.len() {
+            0 => {},
+            1 => rendered.push_str(&format!("\n{gutter} = expected {}", self.expected[0])),
+            _ => rendered.push_str(&format!("\n{gutter} = expected one of: {}", self.expected.join(", "))),
+        }
+        rendered
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::diagnostic::Diagnostic;
+    use crate::proposition::Span;
+
+    #[test]
+    fn test_render() {
+        let diagnostic = Diagnostic::new(
+            "A B)", Span::new(3, 4), "unexpected `)`", &["a binary connective", "end of input"]
+        );
+        let expected = "error: unexpected `)` at 3..4\n  |\n1 | A B)\n  |    ^\n  = expected one of: a binary connective, end of input";
+        assert_eq!(diagnostic.render(), expected);
+    }
+
+    #[test]
+    fn test_render_second_line() {
+        let diagnostic = Diagnostic::new("A\n∀<x> B C", Span::new(2, 5), "wide", &[]);
+        let expected = "error: wide at 2..5\n  |\n2 | ∀<x> B C\n  | ^";
+        assert_eq!(diagnostic.render(), expected);
+
+        let diagnostic = Diagnostic::new("A\n∀<x> B C", Span::new(5, 8), "term", &["a term"]);
+        let expected = "error: term at 5..8\n  |\n2 | ∀<x> B C\n  |  ^^^\n  = expected a term";
+        assert_eq!(diagnostic.render(), expected);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod diagnostic;
+pub mod proposition;
+pub mod sequent;
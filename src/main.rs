@@ -1,6 +1,3 @@
-mod proposition;
-mod sequent;
-
 fn main() {
     println!("Hello, world!");
 }
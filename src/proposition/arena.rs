@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use crate::proposition::atom::Atom;
+use crate::proposition::term::Term;
+use crate::proposition::Proposition;
+
+/// A handle to a proposition interned in an `Arena`. Handles from the same arena are equal
+/// exactly when the propositions they stand for are, so comparing and hashing them is
+/// constant time. They are ordered by when they were first interned, not structurally.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct PropId(u32);
+
+/// One proposition in an arena, with its subformulas replaced by handles.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Node {
+    Atom(Atom),
+    Verum,
+    Falsum,
+    Negation(PropId),
+    Conditional(PropId, PropId),
+    Biconditional(PropId, PropId),
+    Conjunction(Vec<PropId>),
+    Disjunction(Vec<PropId>),
+    Existential(String, PropId),
+    Universal(String, PropId),
+}
+
+impl Node {
+    /// Whether self is an atom or a constant, ie. has no connective to decompose.
+    pub fn is_atomic(&self) -> bool {
+        matches!(self, Node::Atom(_) | Node::Verum | Node::Falsum)
+    }
+
+    /// Return the handles of self's subformulas, in order.
+    pub fn content(&self) -> Vec<PropId> {
+        match self {
+            Node::Atom(_) | Node::Verum | Node::Falsum => Vec::new(),
+            Node::Negation(negatum) => vec![*negatum],
+            Node::Conditional(left, right) | Node::Biconditional(left, right) => vec![*left, *right],
+            Node::Conjunction(props) | Node::Disjunction(props) => props.clone(),
+            Node::Existential(_, predicate) | Node::Universal(_, predicate) => vec![*predicate],
+        }
+    }
+
+    /// Return self with each of its subformulas' handles replaced by f of it.
+    fn map(&self, mut f: impl FnMut(PropId) -> PropId) -> Node {
+        match self {
+            Node::Atom(atom) => Node::Atom(atom.clone()),
+            Node::Verum => Node::Verum,
+            Node::Falsum => Node::Falsum,
+            Node::Negation(negatum) => Node::Negation(f(*negatum)),
+            Node::Conditional(left, right) => Node::Conditional(f(*left), f(*right)),
+            Node::Biconditional(left, right) => Node::Biconditional(f(*left), f(*right)),
+            Node::Conjunction(conjuncts) => Node::Conjunction(conjuncts.iter().map(|id| f(*id)).collect()),
+            Node::Disjunction(disjuncts) => Node::Disjunction(disjuncts.iter().map(|id| f(*id)).collect()),
+            Node::Existential(var, predicate) => Node::Existential(var.clone(), f(*predicate)),
+            Node::Universal(var, predicate) => Node::Universal(var.clone(), f(*predicate)),
+        }
+    }
+}
+
+/// A hash-consed store of propositions. Each distinct proposition, and each distinct
+/// subformula, is stored once, however many times it is interned.
+#[derive(Debug, Default)]
+pub struct Arena {
+    nodes: Vec<Node>,
+    /// The handles of atoms, kept apart from the other nodes so that they can be looked up
+    /// without first being copied into a node.
+    atoms: HashMap<Atom, PropId>,
+    ids: HashMap<Node, PropId>,
+}
+
+impl Arena {
+    pub fn new() -> Arena {
+        Arena::default()
+    }
+
+    /// Return the handle for proposition, storing it and any of its subformulas not already
+    /// in self. Atoms are only copied when they are new to self.
+    pub fn intern(&mut self, proposition: &Proposition) -> PropId {
+        let node = match proposition {
+            Proposition::Atom(atom) => match self.atoms.get(atom) {
+                Some(&id) => return id,
+                None => Node::Atom(atom.clone()),
+            },
+            Proposition::Verum => Node::Verum,
+            Proposition::Falsum => Node::Falsum,
+            Proposition::Negation(negatum) => Node::Negation(self.intern(negatum)),
+            Proposition::Conditional(left, right) => Node::Conditional(self.intern(left), self.intern(right)),
+            Proposition::Biconditional(left, right) => Node::Biconditional(self.intern(left), self.intern(right)),
+            Proposition::Conjunction(conjuncts) => Node::Conjunction(conjuncts.iter().map(|prop| self.intern(prop)).collect()),
+            Proposition::Disjunction(disjuncts) => Node::Disjunction(disjuncts.iter().map(|prop| self.intern(prop)).collect()),
+            Proposition::Existential(var, predicate) => Node::Existential(var.clone(), self.intern(predicate)),
+            Proposition::Universal(var, predicate) => Node::Universal(var.clone(), self.intern(predicate)),
+        };
+        self.insert(node)
+    }
+
+    /// Return the handle for node, storing it if it is not already in self.
+    ///
+    /// # Panics
+    /// Panics if node refers to a handle from another arena.
+    pub fn insert(&mut self, node: Node) -> PropId {
+        let existing = match &node {
+            Node::Atom(atom) => self.atoms.get(atom),
+            _ => self.ids.get(&node),
+        };
+        if let Some(&id) = existing { return id }
+        let id = PropId(self.nodes.len() as u32);
+        self.nodes.push(node.clone());
+        match node {
+            Node::Atom(atom) => self.atoms.insert(atom, id),
+            _ => self.ids.insert(node, id),
+        };
+        id
+    }
+
+    /// Return the node id stands for.
+    ///
+    /// # Panics
+    /// Panics if id is from another arena.
+    pub fn node(&self, id: PropId) -> &Node {
+        &self.nodes[id.0 as usize]
+    }
+
+    /// Rebuild the proposition id stands for.
+    pub fn resolve(&self, id: PropId) -> Proposition {
+        let boxed = |id: &PropId| Box::new(self.resolve(*id));
+        match self.node(id) {
+            Node::Atom(atom) => Proposition::Atom(atom.clone()),
+            Node::Verum => Proposition::Verum,
+            Node::Falsum => Proposition::Falsum,
+            Node::Negation(negatum) => Proposition::Negation(boxed(negatum)),
+            Node::Conditional(left, right) => Proposition::Conditional(boxed(left), boxed(right)),
+            Node::Biconditional(left, right) => Proposition::Biconditional(boxed(left), boxed(right)),
+            Node::Conjunction(conjuncts) => Proposition::Conjunction(conjuncts.iter().map(|id| self.resolve(*id)).collect()),
+            Node::Disjunction(disjuncts) => Proposition::Disjunction(disjuncts.iter().map(|id| self.resolve(*id)).collect()),
+            Node::Existential(var, predicate) => Proposition::Existential(var.clone(), boxed(predicate)),
+            Node::Universal(var, predicate) => Proposition::Universal(var.clone(), boxed(predicate)),
+        }
+    }
+
+    /// Return the atoms in the proposition id stands for, in order, without resolving it.
+    pub fn atoms(&self, id: PropId) -> Vec<&Atom> {
+        match self.node(id) {
+            Node::Atom(atom) => vec![atom],
+            node => node.content().into_iter().flat_map(|id| self.atoms(id)).collect(),
+        }
+    }
+
+    /// Return the handle for the proposition id stands for with each free occurrence of the
+    /// variable var replaced by term, as `Proposition::substitute` does, interning whatever
+    /// is new. Subformulas var doesn't occur free in keep their handles, and only atoms var
+    /// does occur in are copied.
+    pub fn substitute(&mut self, id: PropId, var: &str, term: &Term) -> PropId {
+        let node = match self.node(id) {
+            Node::Atom(atom) if !atom.variables().iter().any(|v| v == var) => return id,
+            Node::Atom(atom) => {
+                let mut atom = atom.clone();
+                atom.substitute(var, term);
+                return self.insert(Node::Atom(atom))
+            },
+            Node::Existential(bound, _) | Node::Universal(bound, _) if bound == var => return id,
+            // renaming a bound variable so that it doesn't capture term's is rare, so rather
+            // than doing it on handles, the proposition is rebuilt
+            Node::Existential(bound, _) | Node::Universal(bound, _) if term.variables().contains(bound) => {
+                let mut proposition = self.resolve(id);
+                proposition.substitute(var, term);
+                return self.intern(&proposition)
+            },
+            node => node.clone(),
+        };
+        let substituted = node.map(|id| self.substitute(id, var, term));
+        if substituted == node { id } else { self.insert(substituted) }
+    }
+
+    /// Return the number of distinct propositions stored in self.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::arena::{Arena, Node};
+    use crate::proposition::term::Term;
+    use crate::proposition::Proposition;
+
+    #[test]
+    fn test_intern_shares_subformulas() {
+        let mut arena = Arena::new();
+        let left: Proposition = "(A & B) > (A & B)".parse().unwrap();
+        let right: Proposition = "~(A & B)".parse().unwrap();
+        let left_id = arena.intern(&left);
+        let right_id = arena.intern(&right);
+        // A, B, (A & B), the conditional and the negation
+        assert_eq!(arena.len(), 5);
+        assert_ne!(left_id, right_id);
+
+        match (arena.node(left_id), arena.node(right_id)) {
+            (Node::Conditional(antecedent, consequent), Node::Negation(negatum)) => {
+                assert_eq!(antecedent, consequent);
+                assert_eq!(antecedent, negatum);
+            },
+            nodes => panic!("unexpected nodes {:?}", nodes),
+        }
+    }
+
+    #[test]
+    fn test_intern_and_resolve() {
+        let mut arena = Arena::new();
+        let proposition: Proposition = "∀<x>(Cat(<x>) > ⊥ v ∃<y> <x> = <y>) <> A & B & C".parse().unwrap();
+        let id = arena.intern(&proposition);
+        assert_eq!(arena.intern(&proposition.clone()), id);
+        assert_eq!(arena.resolve(id), proposition);
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut arena = Arena::new();
+        let proposition: Proposition = "∀<y>(Cat(<x>) > Mammal(<y>)) & ∃<x> Dog(<x>) & A".parse().unwrap();
+        let id = arena.intern(&proposition);
+        let kitty = Term::Name("kitty".to_string());
+        let substituted = arena.substitute(id, "x", &kitty);
+        let mut expected = proposition.clone();
+        expected.substitute("x", &kitty);
+        assert_eq!(arena.resolve(substituted), expected);
+        // only Cat(<kitty>), the universal and conditional it is in, and the conjunction are new
+        let len = arena.len();
+        assert_eq!(arena.intern(&proposition), id);
+        assert_eq!(arena.len(), len);
+        assert_eq!(len, 8 + 4);
+        assert_eq!(arena.substitute(id, "z", &kitty), id);
+
+        // bound variables which would capture term's are renamed
+        let proposition: Proposition = "∀<y> Loves(<x>, <y>)".parse().unwrap();
+        let id = arena.intern(&proposition);
+        let y = Term::Variable("y".to_string());
+        let mut expected = proposition.clone();
+        expected.substitute("x", &y);
+        let renamed = arena.substitute(id, "x", &y);
+        assert_eq!(arena.resolve(renamed), expected);
+        assert_eq!(expected.to_string(), "∀<a>(Loves(<y>, <a>))");
+
+        let atoms: Vec<String> = arena.atoms(substituted).iter().map(|atom| atom.to_string()).collect();
+        assert_eq!(atoms, vec!["Cat(<kitty>)", "Mammal(<y>)", "Dog(<x>)", "A"]);
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use itertools::Itertools;
+use crate::diagnostic::Diagnostic;
+use crate::proposition::lex::{quote, term_end, tokenize, Span, TokenKind};
+use crate::proposition::term::Term;
+use crate::proposition::{Proposition, PropositionCreationError};
+
+/// How an atom's predicate is written.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+pub enum Predicate {
+    /// A symbol written before its parenthesized arguments, eg. `On` in `On(<a>, <the mat>)`.
+    Symbol(String),
+    /// A sentence with a gap for each argument, stored as the text around the gaps, eg.
+    /// `["", " is on ", ""]` for `<a> is on <the mat>`. There is always one more piece of
+    /// text than there are arguments.
+    Prose(Vec<String>),
+    /// Identity between exactly two arguments, written `<a> = <b>`.
+    Identity,
+}
+
+/// An atomic sentence: a predicate and the terms it is applied to.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+pub struct Atom {
+    pub predicate: Predicate,
+    pub args: Vec<Term>,
+}
+
+impl Atom {
+    pub fn new(predicate: Predicate, args: Vec<Term>) -> Atom {
+        Atom { predicate, args }
+    }
+
+    /// Read s as a prose atom. Only `<...>` terms are treated specially, so unlike parsing
+    /// this never fails: connectives, parentheses and so on are just part of the text.
+    pub fn prose(s: &str) -> Atom {
+        let mut pieces: Vec<String> = vec![String::new()];
+        let mut args: Vec<Term> = Vec::new();
+        let mut rest = s;
+        while let Some(open) = rest.find('<') {
+            let close = match term_end(rest, open) {
+                Some(close) => close,
+                None => break,
+            };
+            pieces.last_mut().expect("pieces is never empty").push_str(&rest[..open]);
+            args.push(Term::from_contents(&rest[open + 1..close]));
+            pieces.push(String::new());
+            rest = &rest[close + 1..];
+        }
+        pieces.last_mut().expect("pieces is never empty").push_str(rest);
+        Atom { predicate: Predicate::Prose(pieces), args }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.args.iter().flat_map(|arg| arg.names()).collect()
+    }
+
+    pub fn variables(&self) -> Vec<String> {
+        self.args.iter().flat_map(|arg| arg.variables()).collect()
+    }
+
+    /// Return the function symbols in self's arguments along with their arities.
+    pub fn functions(&self) -> Vec<(String, usize)> {
+        self.args.iter().flat_map(|arg| arg.functions()).collect()
+    }
+
+    /// Return self's arguments and every term nested within them.
+    pub fn subterms(&self) -> Vec<&Term> {
+        self.args.iter().flat_map(|arg| arg.subterms()).collect()
+    }
+
+    /// If self is an identity, return the terms it identifies.
+    pub fn identity(&self) -> Option<(&Term, &Term)> {
+        match (&self.predicate, self.args.as_slice()) {
+            (Predicate::Identity, [left, right]) => Some((left, right)),
+            _ => None,
+        }
+    }
+
+    /// Return each atom made by replacing exactly one occurrence of from in self's
+    /// arguments with to.
+    pub fn replacements(&self, from: &Term, to: &Term) -> Vec<Atom> {
+        let mut replaced: Vec<Atom> = Vec::new();
+        for (index, arg) in self.args.iter().enumerate() {
+            for replacement in arg.replacements(from, to) {
+                let mut atom = self.clone();
+                atom.args[index] = replacement;
+                replaced.push(atom);
+            }
+        }
+        replaced
+    }
+
+    /// Replace each occurrence of the variable var in self's arguments with name.
+    pub fn instantiate(&mut self, var: &str, name: &str) {
+        self.substitute(var, &Term::Name(name.to_string()));
+    }
+
+    /// Replace each occurrence of the variable var in self's arguments, however deeply
+    /// nested, with term.
+    pub fn substitute(&mut self, var: &str, term: &Term) {
+        for arg in self.args.iter_mut() {
+            arg.substitute(var, term);
+        }
+    }
+}
+
+impl FromStr for Atom {
+    type Err = PropositionCreationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<Proposition>()? {
+            Proposition::Atom(atom) => Ok(atom),
+            _ => Err(PropositionCreationError::UnexpectedToken(Diagnostic::new(
+                s, Span::new(0, s.len()), "expected an atomic sentence", &["an atomic sentence"]
+            ))),
+        }
+    }
+}
+
+impl Display for Atom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.predicate {
+            Predicate::Symbol(symbol) => write!(f, "{}({})", symbol, self.args.iter().join(", ")),
+            Predicate::Identity => write!(f, "{}", self.args.iter().join(" = ")),
+            Predicate::Prose(pieces) => {
+                let mut written = String::new();
+                for (index, piece) in pieces.iter().enumerate() {
+                    written.push_str(&display_piece(piece, self.args.is_empty()));
+                    match self.args.get(index) {
+                        // function applications are bracketed to set them off from the prose
+                        Some(arg @ Term::Function(_, _)) => written.push_str(&format!("<{arg}>")),
+                        Some(arg) => written.push_str(&arg.to_string()),
+                        None => {},
+                    }
+                }
+                // an empty atom would otherwise vanish entirely
+                if written.trim().is_empty() { written = quote(&written) }
+                write!(f, "{}", written)
+            },
+        }
+    }
+}
+
+/// Return piece as it should be written in a prose atom, quoting anything (other than
+/// surrounding whitespace) which would not be read back as plain words. `true` and
+/// `false` are read as words except when they make up a whole atom on their own, which
+/// they do if piece is alone, with no arguments around it.
+fn display_piece(piece: &str, alone: bool) -> String {
+    let core = piece.trim();
+    let plain = match tokenize(core) {
+        Ok(tokens) => tokens.iter().all(|token| match token.kind {
+            TokenKind::Word(_) => true,
+            TokenKind::Verum | TokenKind::Falsum => {
+                (!alone || tokens.len() > 1) && core[token.span.start..token.span.end].chars().all(char::is_alphabetic)
+            },
+            _ => false,
+        }),
+        Err(_) => false,
+    };
+    if core.is_empty() || plain { return piece.to_string() }
+
+    let leading = &piece[..piece.len() - piece.trim_start().len()];
+    let trailing = &piece[piece.trim_end().len()..];
+    format!("{leading}{}{trailing}", quote(core))
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::atom::{Atom, Predicate};
+    use crate::proposition::term::Term;
+
+    #[test]
+    fn test_prose() {
+        let atom = Atom::prose("<a> is on <the mat>");
+        assert_eq!(atom.predicate, Predicate::Prose(vec!["".to_string(), " is on ".to_string(), "".to_string()]));
+        assert_eq!(atom.args, vec![Term::Variable("a".to_string()), Term::Name("the mat".to_string())]);
+        assert_eq!(atom.to_string(), "<a> is on <the mat>");
+    }
+
+    #[test]
+    fn test_names_and_variables() {
+        let atom: Atom = "On(<a>, <the mat>)".parse().unwrap();
+        assert_eq!(atom.names(), vec!["the mat".to_string()]);
+        assert_eq!(atom.variables(), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let mut atom: Atom = "On(<a>, <b>)".parse().unwrap();
+        atom.instantiate("a", "kitty");
+        assert_eq!(atom.to_string(), "On(<kitty>, <b>)");
+
+        // names are not variables, even when their contents match
+        let mut atom = Atom::prose("<aa> is on <a>");
+        atom.instantiate("aa", "kitty");
+        assert_eq!(atom.to_string(), "<aa> is on <a>");
+
+        // nor are names written as variables, even when instantiated with a single letter
+        for (source, expected) in [("Cat(<x>)", "Cat(<\"a\">)"), ("<x> is a cat", "<\"a\"> is a cat")] {
+            let mut atom: Atom = source.parse().unwrap();
+            atom.instantiate("x", "a");
+            assert_eq!(atom.to_string(), expected);
+            assert_eq!(atom.to_string().parse::<Atom>().unwrap(), atom);
+            assert!(atom.variables().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_display_quotes_connectives() {
+        let atom = Atom::prose("<a> is or was here");
+        assert_eq!(atom.to_string(), "<a> \"is or was here\"");
+        assert_eq!(atom.to_string().parse::<Atom>().unwrap(), atom);
+
+        assert_eq!(Atom::prose("").to_string(), "\"\"");
+
+        // constants spelled as words only need quoting when they would stand alone
+        assert_eq!(Atom::prose("it is true").to_string(), "it is true");
+        assert_eq!(Atom::prose("<a> is false").to_string(), "<a> is false");
+        assert_eq!(Atom::prose("true").to_string(), "\"true\"");
+        assert_eq!(Atom::prose("it is ⊤").to_string(), "\"it is ⊤\"");
+        for atom in [Atom::prose("it is true"), Atom::prose("<a> is false"), Atom::prose("true")] {
+            assert_eq!(atom.to_string().parse::<Atom>().unwrap(), atom);
+        }
+    }
+
+    #[test]
+    fn test_substitute_into_functions() {
+        let mut atom: Atom = "<mother(<x>)> loves <x>".parse().unwrap();
+        let father = Term::Function("father".to_string(), vec![Term::Name("kitty".to_string())]);
+        atom.substitute("x", &father);
+        assert!(atom.variables().is_empty());
+        assert_eq!(atom.to_string(), "<mother(father(<kitty>))> loves <father(<kitty>)>");
+
+        let mut atom: Atom = "Loves(mother(<x>), <x>)".parse().unwrap();
+        atom.instantiate("x", "kitty");
+        assert_eq!(atom.to_string(), "Loves(mother(<kitty>), <kitty>)");
+        assert_eq!(atom.names(), vec!["kitty".to_string(), "kitty".to_string()]);
+        assert_eq!(atom.functions(), vec![("mother".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_replacements() {
+        let atom: Atom = "Loves(<a>, f(<a>))".parse().unwrap();
+        let a = Term::Variable("a".to_string());
+        let b = Term::Variable("b".to_string());
+        let replaced: Vec<String> = atom.replacements(&a, &b).iter().map(|atom| atom.to_string()).collect();
+        assert_eq!(replaced, vec!["Loves(<b>, f(<a>))", "Loves(<a>, f(<b>))"]);
+
+        let identity: Atom = "<a> = <b>".parse().unwrap();
+        assert_eq!(identity.identity(), Some((&a, &b)));
+        assert_eq!(atom.identity(), None);
+    }
+}
@@ -1,248 +1,443 @@
-use std::iter::Peekable;
-use std::str::Split;
-use itertools::Itertools;
-use crate::proposition::{Proposition, PropositionType};
-
-const NEGATIONS: [&str; 2] = ["~", "not"];
-const CONDITIONALS: [&str; 2] = [">", "implies"];
-const CONJUNCTIONS: [&str; 2] = ["&", "and"];
-const DISJUNCTIONS: [&str; 2] = ["v", "or"];
-const EXISTENTIALS: [&str; 2] = ["∃", "exists"];
-const UNIVERSALS: [&str; 2] = ["∀", "forall"];
-
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
+use crate::proposition::lex::{tokenize, Span, Token, TokenKind};
+use crate::proposition::atom::{Atom, Predicate};
+use crate::proposition::term::Term;
+use crate::proposition::{Proposition, SpanTree};
+
+pub(crate) const NEGATIONS: &[&str] = &["~", "not", "¬", "!", "\\neg", "\\lnot"];
+pub(crate) const CONDITIONALS: &[&str] = &[
+    ">", "implies", "→", "⊃", "->", "\\to", "\\rightarrow", "\\supset", "\\implies"
+];
+pub(crate) const BICONDITIONALS: &[&str] = &[
+    "<>", "iff", "↔", "<->", "\\leftrightarrow", "\\iff"
+];
+pub(crate) const CONJUNCTIONS: &[&str] = &["&", "and", "∧", "/\\", "\\land", "\\wedge"];
+pub(crate) const DISJUNCTIONS: &[&str] = &["v", "or", "∨", "\\/", "\\lor", "\\vee"];
+pub(crate) const VERUMS: &[&str] = &["⊤", "true", "\\top"];
+pub(crate) const FALSUMS: &[&str] = &["⊥", "false", "\\bot"];
+pub(crate) const EXISTENTIALS: &[&str] = &["∃", "exists", "\\exists"];
+pub(crate) const UNIVERSALS: &[&str] = &["∀", "forall", "\\forall"];
+
+/// Descriptions of the tokens which can begin a proposition, for diagnostics.
+const PROPOSITION_STARTS: [&str; 5] = ["an atomic sentence", "`⊤` or `⊥`", "`(`", "`~`", "a quantifier"];
 
 
 pub(crate) fn proposition_from_string(source: String) -> Result<Proposition, PropositionCreationError> {
-    match find_connective(source)? {
-        Connective::Atom(atom) => Ok(Proposition::Atom(atom)),
-        Connective::Unary(connective, content) => make_unary(connective, content),
-        Connective::Binary(left, connective, right) => make_binary(left, connective, right),
-        Connective::Quantifier(quantifier, variable, predicate) => make_quantifier(quantifier, variable, predicate)
+    Ok(proposition_with_spans(&source)?.0)
+}
+
+/// Parse each non-blank line of source as a proposition. Parsing continues past malformed
+/// lines so that every error is reported, each against the whole of source.
+pub(crate) fn propositions_from_lines(source: &str) -> Result<Vec<Proposition>, Vec<PropositionCreationError>> {
+    let mut propositions: Vec<Proposition> = Vec::new();
+    let mut errors: Vec<PropositionCreationError> = Vec::new();
+    let mut offset: usize = 0;
+    for line in source.split('\n') {
+        if !line.trim().is_empty() {
+            match proposition_from_string(line.to_string()) {
+                Ok(proposition) => propositions.push(proposition),
+                Err(error) => errors.push(error.offset(source, offset)),
+            }
+        }
+        offset += line.len() + 1;
     }
+    if errors.is_empty() { Ok(propositions) } else { Err(errors) }
 }
 
-fn make_unary(_connective: String, content: String) -> Result<Proposition, PropositionCreationError> {
-    // add a match statement if ever we add a non-negation unary connective
-    Ok(Proposition::Negation(Box::new(proposition_from_string(content)?)))
+/// Read s as a function application such as `mother(<kitty>)`, if it is one.
+pub(crate) fn function_from_string(s: &str) -> Option<Term> {
+    let tokens = tokenize(s).ok()?;
+    let mut parser = Parser { source: s, tokens, position: 0 };
+    if !matches!(parser.peek()?.kind, TokenKind::Word(_)) { return None }
+    let function = parser.term().ok()?;
+    if parser.peek().is_some() { return None }
+    Some(function)
 }
 
-fn make_binary(left: String, connective: String, right: String) -> Result<Proposition, PropositionCreationError> {
-    match connective.as_str() {
-        ">" | "implies" => Ok(
-            Proposition::Conditional(
-                Box::new(proposition_from_string(left)?),
-                Box::new(proposition_from_string(right)?)
-            )
-        ),
-        "&" | "and" => Ok(
-            Proposition::Conjunction(
-                Box::new(proposition_from_string(left)?),
-                Box::new(proposition_from_string(right)?)
-            )
-        ),
-        "v" | "or" => Ok(
-            Proposition::Disjunction(
-                Box::new(proposition_from_string(left)?),
-                Box::new(proposition_from_string(right)?)
-            )
-        ),
-        _ => Err(PropositionCreationError::InvalidConnective(connective))
+/// Parse source into a proposition, along with the byte spans of the proposition and each
+/// of its subformulas.
+pub(crate) fn proposition_with_spans(source: &str) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+        return Err(PropositionCreationError::EmptyString(Diagnostic::new(
+            source, Span::new(0, source.len()), "empty proposition", &PROPOSITION_STARTS
+        )))
     }
-}
 
-fn make_quantifier(quantifier: String, variable: String, predicate: String) -> Result<Proposition, PropositionCreationError> {
-    match quantifier.as_str() {
-        "∃" | "exists" => Ok(
-            Proposition::Existential(
-                variable,
-                Box::new(proposition_from_string(predicate)?)
-            )
-        ),
-        "∀" | "forall" => Ok(
-            Proposition::Universal(
-                variable,
-                Box::new(proposition_from_string(predicate)?)
-            )
-        ),
-        _ => Err(PropositionCreationError::InvalidConnective(quantifier))
+    let mut parser = Parser { source, tokens, position: 0 };
+    let parsed = parser.formula()?;
+    if let Some(token) = parser.peek() {
+        return Err(parser.unexpected(Some(token), &["a binary connective", "end of input"]));
     }
+    Ok(parsed)
 }
 
-fn deparenthesize(string: &mut String) {
-    while string_first_and_last_chars_are_connected_parens(&string) {
-        string.remove(0);
-        string.remove(string.len() - 1);
-    }
+/// A recursive descent parser over the tokens of a single proposition.
+///
+/// Unparenthesized formulas are grouped by the following table, from tightest to loosest
+/// binding. Negations and quantifiers apply to the smallest formula that follows them.
+///
+/// | connective | precedence | associativity                           |
+/// |------------|------------|-----------------------------------------|
+/// | `~ ∃ ∀`    | 4          | prefix                                  |
+/// | `&`        | 3          | n-ary: `A & B & C` is one conjunction   |
+/// | `v`        | 2          | n-ary: `A v B v C` is one disjunction   |
+/// | `>`        | 1          | right: `A > B > C` is `A > (B > C)`     |
+/// | `<>`       | 0          | right: `A <> B <> C` is `A <> (B <> C)` |
+///
+/// ```text
+/// biconditional := conditional ("<>" biconditional)?
+/// conditional   := disjunction (">" conditional)?
+/// disjunction   := conjunction ("v" conjunction)*
+/// conjunction   := unary ("&" unary)*
+/// unary         := negation unary | quantifier <var> unary | "(" biconditional ")" | "⊤" | "⊥" | atom
+/// atom          := term "=" term | symbol "(" (term ("," term)*)? ")" | (word | <term> | "quotation")+
+/// term          := <term> | symbol "(" (term ("," term)*)? ")"
+/// ```
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
 }
 
-fn string_first_and_last_chars_are_connected_parens(string: &String) -> bool {
-    // if they're not parens, they can't be connected parens
-    if !string.starts_with('(') || !string.ends_with(')') { return false }
-
-    // check connectedness by counting open and closes
-    let mut nestedness: usize = 0;
-    for (index, char) in string.chars().enumerate() {
-        match char {
-            '(' => nestedness += 1,
-            ')' => nestedness -= 1,
-            _ => {}
-        }
-        // nestedness of 0 only occurs at the end of the string for connected parens
-        // nestedness of 0 otherwise means they're not connected, eg. (A v B) & (C > D)
-        if nestedness <= 0 && ((index + 1) < string.len()) { return false }
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
     }
 
-    true
-}
+    fn next_is(&self, kind: &TokenKind) -> bool {
+        self.peek().map(|token| &token.kind) == Some(kind)
+    }
 
-fn proposition_type_from_char(c: char) -> PropositionType {
-    match c {
-        '~' => PropositionType::Negation,
-        '>' => PropositionType::Conditional,
-        '&' => PropositionType::Conjunction,
-        'v' => PropositionType::Disjunction,
-        '∃' => PropositionType::Existential,
-        '∀' => PropositionType::Universal,
-        _ => PropositionType::Atom
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// The span just past the end of the source, for errors about missing tokens.
+    fn end_of_input(&self) -> Span {
+        Span::new(self.source.len(), self.source.len())
     }
-}
 
-fn find_connective(mut s: String) -> Result<Connective, PropositionCreationError> {
-    deparenthesize(&mut s);
-    // peekable to check items without consuming
-    let mut words = s.split(' ').peekable();
+    /// Return an error for finding token (or the end of input, if token is None) where one
+    /// of expected should have been.
+    fn unexpected(&self, token: Option<&Token>, expected: &[&str]) -> PropositionCreationError {
+        match token {
+            Some(token) => {
+                let found = &self.source[token.span.start..token.span.end];
+                PropositionCreationError::UnexpectedToken(Diagnostic::new(
+                    self.source, token.span, &format!("unexpected `{found}`"), expected
+                ))
+            },
+            None => PropositionCreationError::UnexpectedEnd(Diagnostic::new(
+                self.source, self.end_of_input(), "unexpected end of input", expected
+            )),
+        }
+    }
 
-    // empty strings are not connectives
-    if words.peek().is_none() { return Err(PropositionCreationError::EmptyString) }
+    /// Parse a prose atom beginning with first. Terms become the atom's arguments; the text
+    /// around them becomes its predicate, with any spacing between tokens written as a
+    /// single space, so `A  B` and `A B` are the same atom.
+    fn prose_atom(&mut self, first: Token) -> (Proposition, SpanTree) {
+        let mut pieces: Vec<String> = vec![String::new()];
+        let mut args: Vec<Term> = Vec::new();
+        let mut span = first.span;
+        let mut token = first;
+        loop {
+            match &token.kind {
+                TokenKind::Term(contents) => {
+                    args.push(Term::from_contents(contents));
+                    pieces.push(String::new());
+                },
+                // quotations contribute their contents, and words are taken verbatim
+                TokenKind::Quoted(contents) => pieces.last_mut().expect("pieces is never empty").push_str(contents),
+                _ => pieces.last_mut().expect("pieces is never empty").push_str(&self.source[token.span.start..token.span.end]),
+            }
+            match self.peek() {
+                Some(next) if self.is_prose(next) => {
+                    if next.span.start > span.end {
+                        pieces.last_mut().expect("pieces is never empty").push(' ');
+                    }
+                    span = span.to(next.span);
+                    token = self.next().expect("peeked");
+                },
+                _ => break,
+            }
+        }
+        let atom = Atom::new(Predicate::Prose(pieces), args);
+        (Proposition::Atom(atom), SpanTree { span, children: Vec::new() })
+    }
 
-    // check for negation
-    if NEGATIONS.contains(words.peek().expect("words should not be empty")) {
-        return find_negation(&mut words)
+    /// Whether token can be part of a prose atom. `true` and `false` can, as well as being
+    /// constants when they stand alone, so that eg. `it is true` is a sentence.
+    fn is_prose(&self, token: &Token) -> bool {
+        match token.kind {
+            TokenKind::Word(_) | TokenKind::Term(_) | TokenKind::Quoted(_) => true,
+            TokenKind::Verum | TokenKind::Falsum => {
+                self.source[token.span.start..token.span.end].chars().all(char::is_alphabetic)
+            },
+            _ => false,
+        }
     }
 
-    // check for quantifiers
-    let quantifiers: [&str; 4] = <[&str; 4]>::try_from([EXISTENTIALS, UNIVERSALS].concat()).unwrap();
-    if quantifiers.contains(words.peek().expect("words should not be empty")) {
-        return find_quantifier(&mut words)
+    /// Whether token is immediately followed by an opening parenthesis, as the symbols of
+    /// applied predicates and functions are.
+    fn is_applied(&self, token: &Token) -> bool {
+        self.peek().is_some_and(|next| next.kind == TokenKind::LeftParen && next.span.start == token.span.end)
     }
 
-    // check for binaries
-    if let Some(binary) = find_binary(words.clone()) {
-        return binary;
+    /// Whether the token just read begins a term followed by `=`.
+    fn identity_follows(&mut self) -> bool {
+        let position = self.position;
+        self.position -= 1;
+        let follows = self.term().is_ok() && self.next_is(&TokenKind::Equals);
+        self.position = position;
+        follows
     }
 
-    Ok(Connective::Atom(words.join(" ")))
-}
+    /// Parse an identity, starting from the token just read.
+    fn identity(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        self.position -= 1;
+        let start = self.peek().expect("a token was just read").span;
+        let left = self.term()?;
+        self.next();  // the equals sign
+        let right = self.term()?;
+        let end = self.tokens[self.position - 1].span;
+        let atom = Atom::new(Predicate::Identity, vec![left, right]);
+        Ok((Proposition::Atom(atom), SpanTree { span: start.to(end), children: Vec::new() }))
+    }
+
+    /// Parse an atom written as a predicate symbol followed by its parenthesized arguments.
+    fn applied_atom(&mut self, symbol: Token) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let symbol_text = self.source[symbol.span.start..symbol.span.end].to_string();
+        let (args, close) = self.arguments()?;
+        let span = symbol.span.to(close);
+        // predicates without arguments are written as prose
+        let predicate = match args.is_empty() {
+            true => Predicate::Prose(vec![symbol_text]),
+            false => Predicate::Symbol(symbol_text),
+        };
+        Ok((Proposition::Atom(Atom::new(predicate, args)), SpanTree { span, children: Vec::new() }))
+    }
 
-fn find_binary(words: Peekable<Split<char>>) -> Option<Result<Connective, PropositionCreationError>> {
-    let mut nestedness: usize = 0;  // nestedness of the head (index) in parentheses
-    let word_clone = words.clone();  // clone words since we need a copy if successful
-    for (index, word) in word_clone.enumerate() {
-        if nested_word_is_binary_connective(word, nestedness) {
-            return Some(create_binary(words, index))
+    /// Parse a parenthesized, comma separated list of terms, starting from its opening
+    /// parenthesis. Returns the terms and the span of the closing parenthesis.
+    fn arguments(&mut self) -> Result<(Vec<Term>, Span), PropositionCreationError> {
+        self.next();  // the opening parenthesis
+        let mut args: Vec<Term> = Vec::new();
+        if let Some(Token { kind: TokenKind::RightParen, span }) = self.peek().cloned() {
+            self.next();
+            return Ok((args, span))
         }
-        for letter in word.chars() {
-            match letter {
-                '(' => nestedness += 1,
-                ')' => nestedness -= 1,
-                _ => {}
+        loop {
+            args.push(self.term()?);
+            match self.next() {
+                Some(Token { kind: TokenKind::Comma, .. }) => continue,
+                Some(Token { kind: TokenKind::RightParen, span }) => return Ok((args, span)),
+                found => return Err(self.unexpected(found.as_ref(), &["`,`", "`)`"])),
             }
         }
     }
-    None
-}
 
-fn nested_word_is_binary_connective(word: &str, nestedness: usize) -> bool {
-    let binaries: [&str; 6] = <[&str; 6]>::try_from([CONDITIONALS, CONJUNCTIONS, DISJUNCTIONS].concat()).unwrap();
-    nestedness <= 0 && binaries.contains(&word)
-}
+    /// ```text
+    /// term := <term> | symbol "(" (term ("," term)*)? ")"
+    /// ```
+    fn term(&mut self) -> Result<Term, PropositionCreationError> {
+        match self.next() {
+            Some(Token { kind: TokenKind::Term(contents), .. }) => Ok(Term::from_contents(&contents)),
+            Some(token) if matches!(token.kind, TokenKind::Word(_)) && self.is_applied(&token) => {
+                let symbol = self.source[token.span.start..token.span.end].to_string();
+                let (args, _) = self.arguments()?;
+                Ok(Term::Function(symbol, args))
+            },
+            found => Err(self.unexpected(found.as_ref(), &["a term such as `<a>` or `f(<a>)`"])),
+        }
+    }
 
-fn create_binary(mut words: Peekable<Split<char>>, index: usize) -> Result<Connective, PropositionCreationError> {
-    // left side is everything before the connective
-    let mut left: Vec<String> = Vec::new();
-    for _ in 0..index {
-        left.push(String::from(words.next().expect("Words contains at least `index` items")));
+    fn formula(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        self.biconditional()
     }
-    let left: String = left.join(" ");
 
-    let connective: String = String::from(words.next().expect("Words contains at least `index` items"));
+    fn biconditional(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let left = self.conditional()?;
+        if !self.next_is(&TokenKind::Biconditional) { return Ok(left) }
+        self.next();
+        let right = self.biconditional()?;
+        Ok(join(Proposition::Biconditional, left, right))
+    }
 
-    // right side is everything after the connective
-    let mut right: Vec<String> = Vec::new();
-    while let Some(right_word) = words.next() {
-        right.push(String::from(right_word))
+    fn conditional(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let left = self.disjunction()?;
+        if !self.next_is(&TokenKind::Conditional) { return Ok(left) }
+        self.next();
+        // recursing on the right makes conditionals right associative
+        let right = self.conditional()?;
+        Ok(join(Proposition::Conditional, left, right))
     }
-    let right: String = right.join(" ");
 
-    // neither side should be empty
-    if left.len() <= 0 || right.len() <= 0 {
-        let full_string: String = [left, connective, right].join(" ");
-        return Err(PropositionCreationError::MalformedString(full_string));
+    fn disjunction(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let mut disjuncts = vec![self.conjunction()?];
+        while self.next_is(&TokenKind::Disjunction) {
+            self.next();
+            disjuncts.push(self.conjunction()?);
+        }
+        Ok(join_all(Proposition::Disjunction, disjuncts))
     }
 
-    return Ok(Connective::Binary(left, connective, right));
+    fn conjunction(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let mut conjuncts = vec![self.unary()?];
+        while self.next_is(&TokenKind::Conjunction) {
+            self.next();
+            conjuncts.push(self.unary()?);
+        }
+        Ok(join_all(Proposition::Conjunction, conjuncts))
+    }
+
+    fn unary(&mut self) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => return Err(self.unexpected(None, &PROPOSITION_STARTS)),
+        };
+        match token.kind {
+            TokenKind::Word(_) | TokenKind::Term(_) if self.identity_follows() => self.identity(),
+            TokenKind::Negation => {
+                let (negatum, negatum_spans) = self.unary()?;
+                let span = token.span.to(negatum_spans.span);
+                Ok((Proposition::Negation(Box::new(negatum)), SpanTree { span, children: vec![negatum_spans] }))
+            },
+            TokenKind::Existential | TokenKind::Universal => {
+                let var = match self.next() {
+                    // only variables can be bound, not names or function applications
+                    Some(Token { kind: TokenKind::Term(var), .. }) if matches!(Term::from_contents(&var), Term::Variable(_)) => var,
+                    found => return Err(self.unexpected(found.as_ref(), &["a variable such as `<x>`"])),
+                };
+                let (predicate, predicate_spans) = self.unary()?;
+                let span = token.span.to(predicate_spans.span);
+                let proposition = match token.kind {
+                    TokenKind::Existential => Proposition::Existential(var, Box::new(predicate)),
+                    _ => Proposition::Universal(var, Box::new(predicate)),
+                };
+                Ok((proposition, SpanTree { span, children: vec![predicate_spans] }))
+            },
+            TokenKind::Verum | TokenKind::Falsum if self.is_prose(&token) && self.peek().is_some_and(|next| self.is_prose(next)) => {
+                Ok(self.prose_atom(token))
+            },
+            TokenKind::Verum => Ok((Proposition::Verum, SpanTree { span: token.span, children: Vec::new() })),
+            TokenKind::Falsum => Ok((Proposition::Falsum, SpanTree { span: token.span, children: Vec::new() })),
+            TokenKind::LeftParen => {
+                let (inner, mut inner_spans) = self.formula()?;
+                match self.next() {
+                    Some(Token { kind: TokenKind::RightParen, span }) => {
+                        // parenthesized propositions' spans include their parentheses
+                        inner_spans.span = token.span.to(span);
+                        Ok((inner, inner_spans))
+                    },
+                    None => Err(PropositionCreationError::UnclosedParenthesis(Diagnostic::new(
+                        self.source, token.span, "unclosed parenthesis", &["`)`"]
+                    ))),
+                    found => Err(self.unexpected(found.as_ref(), &["a binary connective", "`)`"])),
+                }
+            },
+            TokenKind::Word(_) if self.is_applied(&token) => self.applied_atom(token),
+            TokenKind::Word(_) | TokenKind::Term(_) | TokenKind::Quoted(_) => Ok(self.prose_atom(token)),
+            _ => Err(self.unexpected(Some(&token), &PROPOSITION_STARTS)),
+        }
+    }
 }
 
-fn find_quantifier(words: &mut Peekable<Split<char>>) -> Result<Connective, PropositionCreationError> {
-    let quantifier = words.next().expect("peeked").to_string();
-    let var = match check_for_var(words.next()) {
-        Some(letter) => letter,
-        None => return Err(PropositionCreationError::MalformedString(String::from("improper variable shape")))
-    };
-    let mut predicate = words.join(" ");
-    deparenthesize(&mut predicate);
-    Ok(Connective::Quantifier(quantifier, var, predicate))
+/// Combine two parsed propositions with a binary connective.
+fn join(
+    connective: fn(Box<Proposition>, Box<Proposition>) -> Proposition,
+    (left, left_spans): (Proposition, SpanTree),
+    (right, right_spans): (Proposition, SpanTree),
+) -> (Proposition, SpanTree) {
+    let span = left_spans.span.to(right_spans.span);
+    (
+        connective(Box::new(left), Box::new(right)),
+        SpanTree { span, children: vec![left_spans, right_spans] }
+    )
 }
 
-fn find_negation(words: &mut Peekable<Split<char>>) -> Result<Connective, PropositionCreationError> {
-    let negation = words.next().expect("peeked").to_string();
-    let mut negatum = words.join(" ");
-    deparenthesize(&mut negatum);
-    return Ok(Connective::Unary(negation, negatum));
+/// Combine a chain of parsed propositions with an n-ary connective. A chain of one is just
+/// that proposition.
+fn join_all(
+    connective: fn(Vec<Proposition>) -> Proposition,
+    mut parsed: Vec<(Proposition, SpanTree)>,
+) -> (Proposition, SpanTree) {
+    if parsed.len() == 1 { return parsed.remove(0) }
+    let span = parsed[0].1.span.to(parsed[parsed.len() - 1].1.span);
+    let (propositions, children): (Vec<Proposition>, Vec<SpanTree>) = parsed.into_iter().unzip();
+    (connective(propositions), SpanTree { span, children })
 }
 
-fn check_for_var(word: Option<&str>) -> Option<String> {
-    match word {
-        None => None,
-        Some(string) => {
-            let mut string = String::from(string);
+#[derive(Debug, PartialEq, Clone)]
+pub enum PropositionCreationError {
+    /// The source contained nothing but whitespace.
+    EmptyString(Diagnostic),
+    /// A `<` had no matching `>`.
+    UnclosedTerm(Diagnostic),
+    /// A `"` had no matching `"`.
+    UnclosedQuote(Diagnostic),
+    /// A `(` had no matching `)`.
+    UnclosedParenthesis(Diagnostic),
+    /// A token appeared where it cannot.
+    UnexpectedToken(Diagnostic),
+    /// The source ended before the proposition did.
+    UnexpectedEnd(Diagnostic),
+}
 
-            // names and variables are single letters contained within "<" and ">"
-            if !string.starts_with("<") && !string.ends_with(">") { return None };
-            if !string.len() != 3 { return None }
+impl PropositionCreationError {
+    pub fn diagnostic(&self) -> &Diagnostic {
+        match self {
+            Self::EmptyString(diagnostic) => diagnostic,
+            Self::UnclosedTerm(diagnostic) => diagnostic,
+            Self::UnclosedQuote(diagnostic) => diagnostic,
+            Self::UnclosedParenthesis(diagnostic) => diagnostic,
+            Self::UnexpectedToken(diagnostic) => diagnostic,
+            Self::UnexpectedEnd(diagnostic) => diagnostic,
+        }
+    }
 
-            // we only care about the contents though
-            string.remove(0);
-            string.remove(string.len() - 1);
-            Some(string)
+    /// Move self from a proposition found at offset in source to source itself, eg. to
+    /// report errors in one of a sequent's propositions against the whole sequent.
+    pub fn offset(self, source: &str, offset: usize) -> PropositionCreationError {
+        match self {
+            Self::EmptyString(diagnostic) => Self::EmptyString(diagnostic.offset(source, offset)),
+            Self::UnclosedTerm(diagnostic) => Self::UnclosedTerm(diagnostic.offset(source, offset)),
+            Self::UnclosedQuote(diagnostic) => Self::UnclosedQuote(diagnostic.offset(source, offset)),
+            Self::UnclosedParenthesis(diagnostic) => Self::UnclosedParenthesis(diagnostic.offset(source, offset)),
+            Self::UnexpectedToken(diagnostic) => Self::UnexpectedToken(diagnostic.offset(source, offset)),
+            Self::UnexpectedEnd(diagnostic) => Self::UnexpectedEnd(diagnostic.offset(source, offset)),
         }
     }
 }
 
-enum Connective {
-    Atom(String),
-    Unary(String, String),
-    Binary(String, String, String),
-    Quantifier(String, String, String)
+impl Display for PropositionCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
 }
 
-#[derive(Debug)]
-pub enum PropositionCreationError {
-    MalformedString(String),
-    InvalidConnective(String),
-    EmptyString,
-}
+impl Error for PropositionCreationError {}
 
 
 #[cfg(test)]
 mod test {
-    use crate::proposition::create::proposition_from_string;
-    use crate::proposition::Proposition;
+    use crate::proposition::create::{
+        proposition_from_string, proposition_with_spans, propositions_from_lines, PropositionCreationError
+    };
+    use crate::proposition::lex::Span;
+    use crate::proposition::atom::{Atom, Predicate};
+    use crate::proposition::term::Term;
+    use crate::proposition::{Proposition, SpanTree};
 
     #[test]
     fn test_atom_from_str() {
         let s = "the cat is on the mat".to_string();
-        let expected = Proposition::Atom(s.clone());
+        let expected = Proposition::Atom(Atom::prose(&s));
         let actual = proposition_from_string(s);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -250,8 +445,7 @@ mod test {
     #[test]
     fn test_negation_from_str() {
         let expected = Proposition::Negation(
-            Box::new(Proposition::Atom(
-                String::from("the cat is on the mat"))
+            Box::new(Proposition::Atom(Atom::prose("the cat is on the mat"))
             )
         );
         let symb = "~ (the cat is on the mat)".to_string();
@@ -266,8 +460,8 @@ mod test {
     #[test]
     fn test_conditional_from_str() {
         let expected = Proposition::Conditional(
-            Box::new(Proposition::Atom(String::from("Kitty is a cat"))),
-            Box::new(Proposition::Atom(String::from("Kitty is on the mat")))
+            Box::new(Proposition::Atom(Atom::prose("Kitty is a cat"))),
+            Box::new(Proposition::Atom(Atom::prose("Kitty is on the mat")))
         );
         let symb = "(Kitty is a cat) > (Kitty is on the mat)".to_string();
         let symb = proposition_from_string(symb);
@@ -280,10 +474,10 @@ mod test {
 
     #[test]
     fn test_conjunction_from_str() {
-        let expected = Proposition::Conjunction(
-            Box::new(Proposition::Atom(String::from("Kitty is a cat"))),
-            Box::new(Proposition::Atom(String::from("Kitty is on the mat")))
-        );
+        let expected = Proposition::Conjunction(vec![
+            Proposition::Atom(Atom::prose("Kitty is a cat")),
+            Proposition::Atom(Atom::prose("Kitty is on the mat"))
+        ]);
         let symb = "(Kitty is a cat) & (Kitty is on the mat)".to_string();
         let symb = proposition_from_string(symb);
         assert_eq!(expected, symb.unwrap());
@@ -295,10 +489,10 @@ mod test {
 
     #[test]
     fn test_disjunction_from_str() {
-        let expected = Proposition::Disjunction(
-            Box::new(Proposition::Atom(String::from("Kitty is a cat"))),
-            Box::new(Proposition::Atom(String::from("Kitty is on the mat")))
-        );
+        let expected = Proposition::Disjunction(vec![
+            Proposition::Atom(Atom::prose("Kitty is a cat")),
+            Proposition::Atom(Atom::prose("Kitty is on the mat"))
+        ]);
         let symb = "(Kitty is a cat) v (Kitty is on the mat)".to_string();
         let symb = proposition_from_string(symb);
         assert_eq!(expected, symb.unwrap());
@@ -307,4 +501,346 @@ mod test {
         let char = proposition_from_string(char);
         assert_eq!(expected, char.unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_connectives_without_spaces() {
+        let expected = Proposition::Negation(
+            Box::new(Proposition::Conjunction(vec![
+                Proposition::Atom(Atom::prose("A")),
+                Proposition::Atom(Atom::prose("B"))
+            ]))
+        );
+        assert_eq!(expected, proposition_from_string("~(A&B)".to_string()).unwrap());
+
+        let expected = Proposition::Negation(Box::new(Proposition::Atom(Atom::prose("A"))));
+        assert_eq!(expected, proposition_from_string("~(A)".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_quantifier_from_str() {
+        let expected = Proposition::Universal(
+            String::from("a"),
+            Box::new(Proposition::Atom(Atom::prose("<a> is on <the mat>")))
+        );
+        let symb = proposition_from_string("∀<a>(<a> is on <the mat>)".to_string());
+        assert_eq!(expected, symb.unwrap());
+
+        let char = proposition_from_string("forall <a> (<a> is on <the mat>)".to_string());
+        assert_eq!(expected, char.unwrap());
+    }
+
+    #[test]
+    fn test_aliases_from_str() {
+        let expected = proposition_from_string("∀<x> (~A > B & C v D)".to_string()).unwrap();
+        let unicode = proposition_from_string("∀<x> (¬A → B ∧ C ∨ D)".to_string());
+        assert_eq!(expected, unicode.unwrap());
+
+        let latex = proposition_from_string("\\forall <x> (\\neg A \\to B \\land C \\lor D)".to_string());
+        assert_eq!(expected, latex.unwrap());
+
+        // `!` only negates what directly follows it, so sentences can end in exclamations
+        let exclaimed = proposition_from_string("Hello! & !A".to_string()).unwrap();
+        assert_eq!(exclaimed, Proposition::Conjunction(vec![
+            Proposition::Atom(Atom::prose("Hello!")),
+            Proposition::Negation(Box::new(Proposition::Atom(Atom::prose("A")))),
+        ]));
+        assert_eq!(exclaimed.to_string(), "(Hello! & ~(A))");
+    }
+
+    #[test]
+    fn test_precedence() {
+        let a = || Box::new(Proposition::Atom(Atom::prose("A")));
+        let b = || Box::new(Proposition::Atom(Atom::prose("B")));
+        let c = || Box::new(Proposition::Atom(Atom::prose("C")));
+
+        let expected = Proposition::Conditional(Box::new(Proposition::Conjunction(vec![*a(), *b()])), c());
+        assert_eq!(expected, proposition_from_string("A & B > C".to_string()).unwrap());
+
+        let expected = Proposition::Conditional(a(), Box::new(Proposition::Conjunction(vec![*b(), *c()])));
+        assert_eq!(expected, proposition_from_string("A > B & C".to_string()).unwrap());
+
+        let expected = Proposition::Biconditional(Box::new(Proposition::Conditional(a(), b())), c());
+        assert_eq!(expected, proposition_from_string("A > B iff C".to_string()).unwrap());
+
+        let expected = Proposition::Disjunction(vec![*a(), Proposition::Conjunction(vec![*b(), *c()])]);
+        assert_eq!(expected, proposition_from_string("A v B & C".to_string()).unwrap());
+
+        let expected = Proposition::Conjunction(vec![Proposition::Negation(a()), *b()]);
+        assert_eq!(expected, proposition_from_string("~A & B".to_string()).unwrap());
+
+        let expected = Proposition::Conditional(
+            Box::new(Proposition::Universal(String::from("x"), a())),
+            b()
+        );
+        assert_eq!(expected, proposition_from_string("∀<x> A > B".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_associativity() {
+        let a = || Box::new(Proposition::Atom(Atom::prose("A")));
+        let b = || Box::new(Proposition::Atom(Atom::prose("B")));
+        let c = || Box::new(Proposition::Atom(Atom::prose("C")));
+
+        let expected = Proposition::Conditional(a(), Box::new(Proposition::Conditional(b(), c())));
+        assert_eq!(expected, proposition_from_string("A > B > C".to_string()).unwrap());
+
+        let expected = Proposition::Biconditional(a(), Box::new(Proposition::Biconditional(b(), c())));
+        assert_eq!(expected, proposition_from_string("A <> B <> C".to_string()).unwrap());
+
+        // chains of conjunctions and disjunctions are flattened, but parentheses are respected
+        let expected = Proposition::Conjunction(vec![*a(), *b(), *c()]);
+        assert_eq!(expected, proposition_from_string("A & B & C".to_string()).unwrap());
+
+        let expected = Proposition::Disjunction(vec![*a(), *b(), *c()]);
+        assert_eq!(expected, proposition_from_string("A v B v C".to_string()).unwrap());
+
+        assert_eq!(expected.to_string(), "(A v B v C)");
+        let (_, spans) = proposition_with_spans("A v B v C").unwrap();
+        assert_eq!(spans.span, Span::new(0, 9));
+        assert_eq!(spans.children.len(), 3);
+
+        let mut nested = proposition_from_string("(A v B) v C".to_string()).unwrap();
+        assert_eq!(nested, Proposition::Disjunction(vec![Proposition::Disjunction(vec![*a(), *b()]), *c()]));
+        nested.flatten();
+        assert_eq!(nested, expected);
+    }
+
+    #[test]
+    fn test_spans() {
+        let (_, spans) = proposition_with_spans("~(A & B) > C").unwrap();
+        let expected = SpanTree {
+            span: Span::new(0, 12),
+            children: vec![
+                SpanTree {
+                    span: Span::new(0, 8),
+                    children: vec![SpanTree {
+                        span: Span::new(1, 8),
+                        children: vec![
+                            SpanTree { span: Span::new(2, 3), children: vec![] },
+                            SpanTree { span: Span::new(6, 7), children: vec![] },
+                        ]
+                    }]
+                },
+                SpanTree { span: Span::new(11, 12), children: vec![] },
+            ]
+        };
+        assert_eq!(spans, expected);
+    }
+
+    #[test]
+    fn test_malformed_strings() {
+        assert!(proposition_from_string("".to_string()).is_err());
+        assert!(proposition_from_string("(A & B".to_string()).is_err());
+        assert!(proposition_from_string("A &".to_string()).is_err());
+        assert!(proposition_from_string("A B)".to_string()).is_err());
+        assert!(proposition_from_string("∀ A".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let error = proposition_from_string("(A & B".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnclosedParenthesis(_)));
+        assert_eq!(error.diagnostic().span, Span::new(0, 1));
+
+        let error = proposition_from_string("A &".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnexpectedEnd(_)));
+        assert_eq!(error.diagnostic().span, Span::new(3, 3));
+
+        let error = proposition_from_string("A B)".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnexpectedToken(_)));
+        assert_eq!(error.diagnostic().expected, vec!["a binary connective", "end of input"]);
+
+        let error = proposition_from_string("  ".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::EmptyString(_)));
+
+        let error = proposition_from_string("<a is a cat".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnclosedTerm(_)));
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = proposition_from_string("∀ A".to_string()).unwrap_err();
+        let expected = "error: unexpected `A` at 4..5\n  |\n1 | ∀ A\n  |   ^\n  = expected a variable such as `<x>`";
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn test_only_variables_are_bound() {
+        let error = proposition_from_string("∀<kitty> P(<kitty>)".to_string()).unwrap_err();
+        let expected = "error: unexpected `<kitty>` at 3..10\n  |\n1 | ∀<kitty> P(<kitty>)\n  |  ^^^^^^^\n  = expected a variable such as `<x>`";
+        assert_eq!(error.to_string(), expected);
+
+        let error = proposition_from_string("∀<X> P(<X>)".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnexpectedToken(_)));
+        let error = proposition_from_string("∃<f(<a>)> P(<a>)".to_string()).unwrap_err();
+        assert!(matches!(error, PropositionCreationError::UnexpectedToken(_)));
+    }
+
+    #[test]
+    fn test_quoted_atom_from_str() {
+        let expected = Proposition::Atom(Atom::prose("the vitamin v is or was present"));
+        let quoted = proposition_from_string("\"the vitamin v is or was present\"".to_string());
+        assert_eq!(expected, quoted.unwrap());
+
+        let partly_quoted = proposition_from_string("the vitamin \"v\" is \"or\" was present".to_string());
+        assert_eq!(expected, partly_quoted.unwrap());
+
+        let expected = Proposition::Conjunction(vec![
+            Proposition::Atom(Atom::prose("<a> is (not) here")),
+            Proposition::Atom(Atom::prose("B"))
+        ]);
+        let quoted = proposition_from_string("<a> \"is (not) here\" & B".to_string());
+        assert_eq!(expected, quoted.unwrap());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let sources = [
+            "\"the vitamin v is or was present\" > ~(Kitty is a cat)",
+            "∀<a>(∃<b>(<a> is on <b>) & \"<a> says \\\"hi\\\"\")",
+            "\"not\" v (A v \"\")",
+            "\"A & B\" & C",
+            "(A <-> B) ↔ \"A iff B\"",
+            "⊤ & \\bot > \"true\" v \"⊥\"",
+        ];
+        for source in sources {
+            let proposition = proposition_from_string(source.to_string()).unwrap();
+            let displayed = proposition.to_string();
+            assert_eq!(proposition_from_string(displayed).unwrap(), proposition);
+        }
+
+        // empty joins can't be written, so are displayed as the constants they amount to
+        let empty = Proposition::Conditional(
+            Box::new(Proposition::Conjunction(Vec::new())),
+            Box::new(Proposition::Disjunction(Vec::new())),
+        );
+        assert_eq!(empty.to_string(), "(⊤ > ⊥)");
+        let expected = Proposition::Conditional(Box::new(Proposition::Verum), Box::new(Proposition::Falsum));
+        assert_eq!(proposition_from_string(empty.to_string()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_propositions_from_lines() {
+        let propositions = propositions_from_lines("A & B\n\n~C\n").unwrap();
+        assert_eq!(propositions.len(), 2);
+
+        let errors = propositions_from_lines("A &\nB\n(C\nD").unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], PropositionCreationError::UnexpectedEnd(_)));
+        assert!(matches!(errors[1], PropositionCreationError::UnclosedParenthesis(_)));
+        assert_eq!(errors[1].diagnostic().span, Span::new(6, 7));
+        assert!(errors[1].to_string().contains("3 | (C"));
+    }
+
+    #[test]
+    fn test_applied_atom_from_str() {
+        let expected = Proposition::Atom(Atom::new(
+            Predicate::Symbol(String::from("On")),
+            vec![Term::Variable(String::from("a")), Term::Name(String::from("the mat"))]
+        ));
+        assert_eq!(expected, proposition_from_string("On(<a>, <the mat>)".to_string()).unwrap());
+        assert_eq!(expected, proposition_from_string("(On(<a>,<the mat>))".to_string()).unwrap());
+
+        let expected = Proposition::Negation(Box::new(Proposition::Atom(Atom::new(
+            Predicate::Symbol(String::from("Cat")),
+            vec![Term::Name(String::from("kitty"))]
+        ))));
+        assert_eq!(expected, proposition_from_string("~Cat(<kitty>)".to_string()).unwrap());
+
+        assert_eq!(Proposition::Atom(Atom::prose("P")), proposition_from_string("P()".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_prose_atom_from_str() {
+        let expected = Proposition::Atom(Atom::new(
+            Predicate::Prose(vec![String::from(""), String::from(" is on "), String::from("")]),
+            vec![Term::Variable(String::from("a")), Term::Name(String::from("the mat"))]
+        ));
+        assert_eq!(expected, proposition_from_string("<a> is on <the mat>".to_string()).unwrap());
+        assert_eq!(expected, proposition_from_string("<a>  is\ton\n<the mat>".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_prose_spacing() {
+        let expected = Proposition::Atom(Atom::prose("the cat is on the mat"));
+        assert_eq!(expected, proposition_from_string("the  cat is\non   the mat".to_string()).unwrap());
+        assert_eq!(proposition_from_string("A  B".to_string()), proposition_from_string("A B".to_string()));
+        // terms written right next to the text stay that way
+        assert_eq!(proposition_from_string("<a>'s cat".to_string()).unwrap().to_string(), "<a>'s cat");
+    }
+
+    #[test]
+    fn test_malformed_applied_atoms() {
+        assert!(proposition_from_string("On(<a>".to_string()).is_err());
+        assert!(proposition_from_string("On(<a> <b>)".to_string()).is_err());
+        assert!(proposition_from_string("On(a, <b>)".to_string()).is_err());
+        assert!(proposition_from_string("On(<a>,)".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_function_terms_from_str() {
+        let kitty = Term::Name(String::from("kitty"));
+        let expected = Proposition::Atom(Atom::new(
+            Predicate::Symbol(String::from("Cat")),
+            vec![Term::Function(
+                String::from("mother"),
+                vec![Term::Function(String::from("father"), vec![kitty.clone()])]
+            )]
+        ));
+        assert_eq!(expected, proposition_from_string("Cat(mother(father(<kitty>)))".to_string()).unwrap());
+
+        let expected = Proposition::Atom(Atom::new(
+            Predicate::Prose(vec![String::from(""), String::from(" is on "), String::from("")]),
+            vec![Term::Function(String::from("f"), vec![kitty, Term::Variable(String::from("x"))]), Term::Name(String::from("the mat"))]
+        ));
+        let parsed = proposition_from_string("<f(<kitty>, <x>)> is on <the mat>".to_string()).unwrap();
+        assert_eq!(expected, parsed);
+        assert_eq!(parsed.to_string(), "<f(<kitty>, <x>)> is on <the mat>");
+
+        assert!(proposition_from_string("Cat(mother(<kitty>)".to_string()).is_err());
+        assert!(proposition_from_string("Cat(mother (<kitty>))".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_constants_from_str() {
+        let expected = Proposition::Conditional(Box::new(Proposition::Falsum), Box::new(Proposition::Verum));
+        assert_eq!(expected, proposition_from_string("⊥ > ⊤".to_string()).unwrap());
+        assert_eq!(expected, proposition_from_string("false implies true".to_string()).unwrap());
+        assert_eq!(expected, proposition_from_string("\\bot \\to \\top".to_string()).unwrap());
+        assert_eq!(expected.to_string(), "(⊥ > ⊤)");
+        assert_eq!(expected.complexity(), 1);
+
+        // spelled as words, constants only stand alone, and are otherwise part of sentences
+        assert_eq!(Proposition::Atom(Atom::prose("it is true")), proposition_from_string("it is true".to_string()).unwrap());
+        assert_eq!(Proposition::Atom(Atom::prose("true love")), proposition_from_string("true love".to_string()).unwrap());
+        let expected = Proposition::Conjunction(vec![Proposition::Atom(Atom::prose("<a> is false")), Proposition::Verum]);
+        assert_eq!(expected, proposition_from_string("<a> is false & true".to_string()).unwrap());
+        assert!(proposition_from_string("it is ⊤".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_identity_from_str() {
+        let expected = Proposition::Atom(Atom::new(
+            Predicate::Identity,
+            vec![Term::Name(String::from("the morning star")), Term::Name(String::from("the evening star"))]
+        ));
+        let parsed = proposition_from_string("<the morning star> = <the evening star>".to_string()).unwrap();
+        assert_eq!(expected, parsed);
+        assert_eq!(parsed.to_string(), "<the morning star> = <the evening star>");
+
+        let expected = Proposition::Negation(Box::new(Proposition::Atom(Atom::new(
+            Predicate::Identity,
+            vec![
+                Term::Function(String::from("f"), vec![Term::Variable(String::from("x"))]),
+                Term::Variable(String::from("x")),
+            ]
+        ))));
+        assert_eq!(expected, proposition_from_string("~f(<x>)=<x>".to_string()).unwrap());
+
+        let (_, spans) = proposition_with_spans("A & <a> = <b>").unwrap();
+        assert_eq!(spans.children[1].span, Span::new(4, 13));
+
+        assert!(proposition_from_string("<a> =".to_string()).is_err());
+        assert!(proposition_from_string("the morning star = <b>".to_string()).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
+use crate::proposition::create::{
+    PropositionCreationError, BICONDITIONALS, CONDITIONALS, CONJUNCTIONS, DISJUNCTIONS, EXISTENTIALS, FALSUMS,
+    NEGATIONS, UNIVERSALS, VERUMS
+};
+use crate::sequent::create::{LOGICAL_TURNSTILES, MATERIAL_TURNSTILES};
+use crate::sequent::Turnstile;
+
+/// A half-open range of byte offsets into the source string.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// Return the smallest span covering both self and other.
+    pub fn to(&self, other: Span) -> Span {
+        Span { start: self.start.min(other.start), end: self.end.max(other.end) }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum TokenKind {
+    Negation,
+    Conditional,
+    Biconditional,
+    Conjunction,
+    Disjunction,
+    Existential,
+    Universal,
+    Verum,
+    Falsum,
+    LeftParen,
+    RightParen,
+    /// Separates the propositions on each side of a sequent, and the arguments of predicates.
+    Comma,
+    /// Asserts that two terms are identical.
+    Equals,
+    Turnstile(Turnstile),
+    /// The contents of a `<...>` term, without the angle brackets.
+    Term(String),
+    /// The contents of a `"..."` quotation, with escapes removed.
+    Quoted(String),
+    /// Any other run of characters, eg. one word of an atomic sentence.
+    Word(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// Split source into tokens. Connectives, parentheses and terms are recognised
+/// whether or not they are surrounded by whitespace, except for connectives spelled
+/// as words (eg. `and`) or LaTeX commands (eg. `\land`), which must stand alone, and
+/// `!`, which is only a negation directly before what it negates, as in `!A`.
+pub(crate) fn tokenize(source: &str) -> Result<Vec<Token>, PropositionCreationError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut start: usize = 0;
+
+    while let Some(c) = source[start..].chars().next() {
+        if c.is_whitespace() {
+            start += c.len_utf8();
+            continue;
+        }
+
+        // terms run from "<" to its matching ">", and may contain spaces and nested terms,
+        // unless the "<" begins a biconditional such as "<>"
+        if c == '<' && symbol_at(&source[start..]).is_none() {
+            let end = match term_end(source, start) {
+                Some(end) => end,
+                None => return Err(PropositionCreationError::UnclosedTerm(Diagnostic::new(
+                    source, Span::new(start, start + 1), "unclosed term", &["`>`"]
+                ))),
+            };
+            let span = Span::new(start, end + 1);
+            tokens.push(Token { kind: TokenKind::Term(source[start + 1..end].to_string()), span });
+            start = span.end;
+            continue;
+        }
+
+        // quotations run to the next unescaped '"', and may contain anything
+        if c == '"' {
+            let (contents, end) = match unquote(source, start) {
+                Some(unquoted) => unquoted,
+                None => return Err(PropositionCreationError::UnclosedQuote(Diagnostic::new(
+                    source, Span::new(start, start + 1), "unclosed quotation", &["`\"`"]
+                ))),
+            };
+            tokens.push(Token { kind: TokenKind::Quoted(contents), span: Span::new(start, end) });
+            start = end;
+            continue;
+        }
+
+        if "(),=".contains(c) {
+            let kind = match c {
+                '(' => TokenKind::LeftParen,
+                ')' => TokenKind::RightParen,
+                ',' => TokenKind::Comma,
+                _ => TokenKind::Equals,
+            };
+            tokens.push(Token { kind, span: Span::new(start, start + 1) });
+            start += 1;
+            continue;
+        }
+
+        if let Some((symbol, kind)) = symbol_at(&source[start..]).filter(|(symbol, _)| !is_prefix_only(symbol, &source[start..])) {
+            tokens.push(Token { kind, span: Span::new(start, start + symbol.len()) });
+            start += symbol.len();
+            continue;
+        }
+
+        // LaTeX commands are a backslash followed by letters
+        let end = if c == '\\' {
+            let length = source[start + 1..].find(|c: char| !c.is_alphabetic()).unwrap_or(source.len() - start - 1);
+            start + 1 + length
+        } else {
+            word_end(source, start)
+        };
+        let word = &source[start..end];
+        let kind = match PREFIX_ONLY.contains(&word) {
+            true => TokenKind::Word(word.to_string()),
+            false => connective_kind(word).unwrap_or_else(|| TokenKind::Word(word.to_string())),
+        };
+        tokens.push(Token { kind, span: Span::new(start, end) });
+        start = end;
+    }
+
+    Ok(tokens)
+}
+
+/// Return the index of the ">" matching the "<" at start, if there is one.
+pub(crate) fn term_end(source: &str, start: usize) -> Option<usize> {
+    let mut nestedness: usize = 0;
+    for (index, c) in source[start..].char_indices() {
+        match c {
+            '<' => nestedness += 1,
+            '>' => nestedness -= 1,
+            _ => {},
+        }
+        if nestedness == 0 { return Some(start + index) }
+    }
+    None
+}
+
+/// Read the quotation starting at start, returning its contents with backslash escapes
+/// removed and the index just past its closing quote. Returns None if it is never closed.
+pub(crate) fn unquote(source: &str, start: usize) -> Option<(String, usize)> {
+    let mut contents = String::new();
+    let mut chars = source[start + 1..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((contents, start + 1 + index + 1)),
+            '\\' => contents.push(chars.next()?.1),
+            _ => contents.push(c),
+        }
+    }
+    None
+}
+
+/// Surround s with quotes, escaping any quotes or backslashes it contains.
+pub(crate) fn quote(s: &str) -> String {
+    let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Return the index just past the word starting at start. Words end at whitespace,
+/// parentheses, commas, equals signs, terms, quotations, symbolic connectives and LaTeX commands.
+fn word_end(source: &str, start: usize) -> usize {
+    let mut end = start;
+    for (index, c) in source[start..].char_indices() {
+        let rest = &source[start + index..];
+        let symbol = symbol_at(rest).is_some_and(|(symbol, _)| !PREFIX_ONLY.contains(&symbol));
+        if index > 0 && (c.is_whitespace() || "(),=<\"\\".contains(c) || symbol) { break }
+        end = start + index + c.len_utf8();
+    }
+    end
+}
+
+/// The tables of spellings for each connective, constant and turnstile.
+fn connectives() -> [(&'static [&'static str], TokenKind); 11] {
+    [
+        (MATERIAL_TURNSTILES, TokenKind::Turnstile(Turnstile::Material)),
+        (LOGICAL_TURNSTILES, TokenKind::Turnstile(Turnstile::Logical)),
+        (NEGATIONS, TokenKind::Negation),
+        (CONDITIONALS, TokenKind::Conditional),
+        (BICONDITIONALS, TokenKind::Biconditional),
+        (CONJUNCTIONS, TokenKind::Conjunction),
+        (DISJUNCTIONS, TokenKind::Disjunction),
+        (EXISTENTIALS, TokenKind::Existential),
+        (UNIVERSALS, TokenKind::Universal),
+        (VERUMS, TokenKind::Verum),
+        (FALSUMS, TokenKind::Falsum),
+    ]
+}
+
+/// Symbolic spellings are those which are neither words nor LaTeX commands.
+fn is_symbol(spelling: &str) -> bool {
+    let is_word = spelling.chars().all(char::is_alphanumeric);
+    let is_command = spelling.starts_with('\\') && spelling[1..].chars().all(char::is_alphabetic);
+    !is_word && !is_command
+}
+
+/// Symbolic connectives which are also punctuation, so are only connectives at the start
+/// of a word and directly before what they apply to. This keeps eg. `Hello!` and `Hello !`
+/// prose.
+const PREFIX_ONLY: &[&str] = &["!"];
+
+/// Whether symbol, found at the start of s, is one of `PREFIX_ONLY` without an operand
+/// directly after it, and so is part of a word rather than a connective.
+fn is_prefix_only(symbol: &str, s: &str) -> bool {
+    let operand = s[symbol.len()..].chars().next().is_some_and(|c| !c.is_whitespace() && !"),".contains(c));
+    PREFIX_ONLY.contains(&symbol) && !operand
+}
+
+/// Return the longest symbolic connective at the start of s, if there is one.
+fn symbol_at(s: &str) -> Option<(&'static str, TokenKind)> {
+    let mut found: Option<(&'static str, TokenKind)> = None;
+    for (spellings, kind) in connectives() {
+        for &spelling in spellings.iter().filter(|spelling| is_symbol(spelling)) {
+            let longer = found.as_ref().is_none_or(|(symbol, _)| spelling.len() > symbol.len());
+            if s.starts_with(spelling) && longer {
+                found = Some((spelling, kind.clone()));
+            }
+        }
+    }
+    found
+}
+
+fn connective_kind(s: &str) -> Option<TokenKind> {
+    connectives()
+        .into_iter()
+        .find(|(spellings, _)| spellings.contains(&s))
+        .map(|(_, kind)| kind)
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::lex::{quote, tokenize, Span, TokenKind};
+    use crate::sequent::Turnstile;
+
+    fn kinds(s: &str) -> Vec<TokenKind> {
+        tokenize(s).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn test_tokenize_without_spaces() {
+        assert_eq!(
+            kinds("(A&B)"),
+            vec![
+                TokenKind::LeftParen,
+                TokenKind::Word("A".to_string()),
+                TokenKind::Conjunction,
+                TokenKind::Word("B".to_string()),
+                TokenKind::RightParen,
+            ]
+        );
+        assert_eq!(
+            kinds("~(A)"),
+            vec![
+                TokenKind::Negation,
+                TokenKind::LeftParen,
+                TokenKind::Word("A".to_string()),
+                TokenKind::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_words() {
+        assert_eq!(
+            kinds("vitamin v and"),
+            vec![
+                TokenKind::Word("vitamin".to_string()),
+                TokenKind::Disjunction,
+                TokenKind::Conjunction,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_terms() {
+        let tokens = tokenize("∀<a>(<a> is on <the mat>)").unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Universal);
+        assert_eq!(tokens[0].span, Span::new(0, 3));
+        assert_eq!(tokens[1].kind, TokenKind::Term("a".to_string()));
+        assert_eq!(tokens[1].span, Span::new(3, 6));
+        assert_eq!(tokens[6].kind, TokenKind::Term("the mat".to_string()));
+        assert_eq!(tokens[6].span, Span::new(17, 26));
+    }
+
+    #[test]
+    fn test_tokenize_unclosed_term() {
+        assert!(tokenize("<a is on the mat").is_err());
+    }
+
+    #[test]
+    fn test_tokenize_aliases() {
+        let expected = vec![
+            TokenKind::Negation,
+            TokenKind::Word("A".to_string()),
+            TokenKind::Conditional,
+            TokenKind::Word("B".to_string()),
+            TokenKind::Conjunction,
+            TokenKind::Word("C".to_string()),
+            TokenKind::Disjunction,
+            TokenKind::Word("D".to_string()),
+        ];
+        assert_eq!(kinds("¬A → B ∧ C ∨ D"), expected);
+        assert_eq!(kinds("!A->B/\\C\\/D"), expected);
+        assert_eq!(kinds("\\neg A \\to B \\land C \\lor D"), expected);
+        assert_eq!(kinds("~A ⊃ B & C v D"), expected);
+    }
+
+    #[test]
+    fn test_tokenize_exclamations() {
+        assert_eq!(kinds("Hello!"), vec![TokenKind::Word("Hello!".to_string())]);
+        assert_eq!(kinds("Hello !"), vec![TokenKind::Word("Hello".to_string()), TokenKind::Word("!".to_string())]);
+        assert_eq!(kinds("A&!B")[2], TokenKind::Negation);
+        assert_eq!(kinds("!!(A)")[..2], [TokenKind::Negation, TokenKind::Negation]);
+    }
+
+    #[test]
+    fn test_tokenize_biconditionals() {
+        let expected = vec![
+            TokenKind::Term("a".to_string()),
+            TokenKind::Biconditional,
+            TokenKind::Term("b".to_string()),
+        ];
+        assert_eq!(kinds("<a> <> <b>"), expected);
+        assert_eq!(kinds("<a><-><b>"), expected);
+        assert_eq!(kinds("<a> ↔ <b>"), expected);
+        assert_eq!(kinds("<a> iff <b>"), expected);
+        assert_eq!(kinds("<a> \\leftrightarrow <b>"), expected);
+        assert_eq!(kinds("A<>B")[1], TokenKind::Biconditional);
+    }
+
+    #[test]
+    fn test_tokenize_quantifier_aliases() {
+        let expected = vec![
+            TokenKind::Universal,
+            TokenKind::Term("x".to_string()),
+            TokenKind::Existential,
+            TokenKind::Term("y".to_string()),
+        ];
+        assert_eq!(kinds("∀<x>∃<y>"), expected);
+        assert_eq!(kinds("\\forall<x>\\exists<y>"), expected);
+    }
+
+    #[test]
+    fn test_tokenize_word_aliases_stand_alone() {
+        assert_eq!(kinds("notable"), vec![TokenKind::Word("notable".to_string())]);
+        assert_eq!(kinds("\\landmark"), vec![TokenKind::Word("\\landmark".to_string())]);
+        assert_eq!(kinds("well-known"), vec![TokenKind::Word("well-known".to_string())]);
+    }
+
+    #[test]
+    fn test_tokenize_quotations() {
+        assert_eq!(
+            kinds("\"A & B\"&C"),
+            vec![
+                TokenKind::Quoted("A & B".to_string()),
+                TokenKind::Conjunction,
+                TokenKind::Word("C".to_string()),
+            ]
+        );
+        assert_eq!(kinds(r#""say \"hi\" \\ bye""#), vec![TokenKind::Quoted(r#"say "hi" \ bye"#.to_string())]);
+        assert!(tokenize("\"A & B").is_err());
+    }
+
+    #[test]
+    fn test_quote() {
+        let s = r#"say "hi" \ bye"#;
+        assert_eq!(kinds(&quote(s)), vec![TokenKind::Quoted(s.to_string())]);
+    }
+
+    #[test]
+    fn test_tokenize_sequent_punctuation() {
+        assert_eq!(
+            kinds("A,B|~C|-⊢"),
+            vec![
+                TokenKind::Word("A".to_string()),
+                TokenKind::Comma,
+                TokenKind::Word("B".to_string()),
+                TokenKind::Turnstile(Turnstile::Material),
+                TokenKind::Word("C".to_string()),
+                TokenKind::Turnstile(Turnstile::Logical),
+                TokenKind::Turnstile(Turnstile::Logical),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_nested_terms() {
+        assert_eq!(
+            kinds("<mother(<kitty>)> > <a>"),
+            vec![
+                TokenKind::Term("mother(<kitty>)".to_string()),
+                TokenKind::Conditional,
+                TokenKind::Term("a".to_string()),
+            ]
+        );
+    }
+}
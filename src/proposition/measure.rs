@@ -0,0 +1,161 @@
+use crate::proposition::normal::Quantifier;
+use crate::proposition::visit::Fold;
+use crate::proposition::Proposition;
+
+/// A way of measuring how complex a proposition or sequent is, for `Proposition::measure`
+/// and `Sequent::measure`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Measure {
+    /// The number of connectives, counting an n-ary conjunction or disjunction as the n - 1
+    /// binary ones it abbreviates. See `Proposition::connective_count`.
+    Connectives,
+    /// How deeply connectives are nested. See `Proposition::depth`.
+    Depth,
+    /// The number of occurrences of atoms. See `Proposition::atom_count`.
+    Atoms,
+    /// How deeply quantifiers are nested. See `Proposition::quantifier_rank`.
+    QuantifierRank,
+    /// The number of alternating blocks of quantifiers. See
+    /// `Proposition::alternation_depth`.
+    AlternationDepth,
+}
+
+impl Proposition {
+    /// Return the complexity of self according to measure.
+    pub fn measure(&self, measure: Measure) -> usize {
+        match measure {
+            Measure::Connectives => self.connective_count(),
+            Measure::Depth => self.depth(),
+            Measure::Atoms => self.atom_count(),
+            Measure::QuantifierRank => self.quantifier_rank(),
+            Measure::AlternationDepth => self.alternation_depth(),
+        }
+    }
+
+    /// Return the number of connectives in self. An n-ary conjunction or disjunction counts
+    /// as the n - 1 binary ones it abbreviates, so flattening doesn't change the count, and
+    /// `⊤` and `⊥` count as atomic rather than as connectives.
+    pub fn connective_count(&self) -> usize {
+        struct Connectives;
+        impl Fold for Connectives {
+            type Output = usize;
+            fn fold(&mut self, proposition: &Proposition, content: Vec<usize>) -> usize {
+                let own = match proposition {
+                    Proposition::Atom(_) | Proposition::Verum | Proposition::Falsum => 0,
+                    Proposition::Conjunction(props) | Proposition::Disjunction(props) => props.len().saturating_sub(1),
+                    _ => 1,
+                };
+                own + content.into_iter().sum::<usize>()
+            }
+        }
+        self.fold(&mut Connectives)
+    }
+
+    /// Return the depth to which connectives are nested in self: 0 for atoms and constants,
+    /// and one more than the deepest of its content for anything else.
+    pub fn depth(&self) -> usize {
+        struct Depth;
+        impl Fold for Depth {
+            type Output = usize;
+            fn fold(&mut self, proposition: &Proposition, content: Vec<usize>) -> usize {
+                match proposition {
+                    Proposition::Atom(_) | Proposition::Verum | Proposition::Falsum => 0,
+                    _ => 1 + content.into_iter().max().unwrap_or(0),
+                }
+            }
+        }
+        self.fold(&mut Depth)
+    }
+
+    /// Return the number of occurrences of atoms in self, counting repeated atoms each time
+    /// they occur.
+    pub fn atom_count(&self) -> usize {
+        self.atoms().len()
+    }
+
+    /// Return the depth to which quantifiers are nested in self, ignoring other connectives.
+    pub fn quantifier_rank(&self) -> usize {
+        struct Rank;
+        impl Fold for Rank {
+            type Output = usize;
+            fn fold(&mut self, proposition: &Proposition, content: Vec<usize>) -> usize {
+                let deepest = content.into_iter().max().unwrap_or(0);
+                match proposition {
+                    Proposition::Atom(_) => 0,
+                    Proposition::Existential(_, _) | Proposition::Universal(_, _) => 1 + deepest,
+                    _ => deepest,
+                }
+            }
+        }
+        self.fold(&mut Rank)
+    }
+
+    /// Return the greatest number of blocks of like quantifiers nested along any branch of
+    /// self, once negations have been pushed inwards, so that eg. a negated universal counts
+    /// as an existential. This is 0 when self is quantifier-free, 1 for `∀<x> ∀<y> P(<x>, <y>)`
+    /// and 2 for `∀<x> ∃<y> P(<x>, <y>)`, the n of the Σn or Πn class self belongs to.
+    pub fn alternation_depth(&self) -> usize {
+        blocks(&self.nnf(), None)
+    }
+}
+
+/// Return the most blocks of quantifiers along any branch of proposition, which is in
+/// negation normal form, when it is directly inside a quantifier of kind outer.
+fn blocks(proposition: &Proposition, outer: Option<Quantifier>) -> usize {
+    let quantifier = match proposition {
+        Proposition::Atom(_) => return 0,
+        Proposition::Existential(_, _) => Some(Quantifier::Existential),
+        Proposition::Universal(_, _) => Some(Quantifier::Universal),
+        _ => None,
+    };
+    let deepest = proposition.content().into_iter().map(|prop| blocks(prop, quantifier.or(outer))).max().unwrap_or(0);
+    match quantifier {
+        Some(_) if quantifier != outer => 1 + deepest,
+        _ => deepest,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::measure::Measure;
+    use crate::proposition::Proposition;
+    use crate::sequent::Sequent;
+
+    fn measures(s: &str) -> [usize; 5] {
+        let proposition: Proposition = s.parse().unwrap();
+        [Measure::Connectives, Measure::Depth, Measure::Atoms, Measure::QuantifierRank, Measure::AlternationDepth]
+            .map(|measure| proposition.measure(measure))
+    }
+
+    #[test]
+    fn test_measures() {
+        assert_eq!(measures("A"), [0, 0, 1, 0, 0]);
+        assert_eq!(measures("⊤ > ⊥"), [1, 1, 0, 0, 0]);
+        assert_eq!(measures("A & B & C & ~A"), [4, 2, 4, 0, 0]);
+        assert_eq!(measures("((A & B) & C) & ~A"), [4, 3, 4, 0, 0]);
+        assert_eq!(measures("∀<x> ∀<y> P(<x>, <y>)"), [2, 2, 1, 2, 1]);
+        assert_eq!(measures("∀<x> ∃<y> P(<x>, <y>)"), [2, 2, 1, 2, 2]);
+        assert_eq!(measures("∀<x> ~∀<y> P(<x>, <y>)"), [3, 3, 1, 2, 2]);
+        assert_eq!(measures("∀<x> P(<x>) & ∃<y> ∀<z> Q(<y>, <z>)"), [4, 3, 2, 2, 2]);
+        assert_eq!(measures("∃<x> (∀<y> P(<y>) > Q(<x>))"), [3, 3, 2, 2, 1]);
+        // either side of a biconditional is both negated and not, but on different branches
+        assert_eq!(measures("∃<x> P(<x>) <> A"), [2, 2, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_sequent_measures() {
+        let sequent: Sequent = "A & B, ∀<x> P(<x>) |~ ∀<x> ∃<y> R(<x>, <y>), ~C".parse().unwrap();
+        let measure = |measure| sequent.measure(measure);
+        assert_eq!(measure(Measure::Connectives), 5);
+        assert_eq!(measure(Measure::Depth), 2);
+        assert_eq!(measure(Measure::Atoms), 5);
+        assert_eq!(measure(Measure::QuantifierRank), 2);
+        assert_eq!(measure(Measure::AlternationDepth), 2);
+        assert_eq!(sequent.complexity(), 3);
+
+        // quantifiers in different propositions are never nested in one another
+        let sequent: Sequent = "∃<x> ∀<y> R(<x>, <y>) |~ ∀<z> P(<z>)".parse().unwrap();
+        assert_eq!(sequent.measure(Measure::AlternationDepth), 2);
+    }
+}
@@ -1,153 +1,325 @@
 pub(crate) mod create;
+pub(crate) mod lex;
+pub(crate) mod path;
+mod arena;
+mod atom;
+mod measure;
+mod normal;
+mod simplify;
+mod structure;
+mod term;
+mod valuation;
+mod visit;
 
-use lazy_static::lazy_static;
-use std::cmp;
 use std::fmt::{Display, Formatter};
-use regex::Regex;
-use crate::proposition::create::proposition_from_string;
-
-#[derive(Debug, PartialEq)]
+use std::str::FromStr;
+use itertools::Itertools;
+use crate::proposition::create::{proposition_from_string, proposition_with_spans, propositions_from_lines};
+
+pub use crate::proposition::arena::{Arena, Node, PropId};
+pub use crate::proposition::atom::{Atom, Predicate};
+pub use crate::proposition::create::PropositionCreationError;
+pub use crate::proposition::lex::Span;
+pub use crate::proposition::measure::Measure;
+pub use crate::proposition::normal::Quantifier;
+pub use crate::proposition::path::{Path, Polarity, Subformulas};
+pub use crate::proposition::simplify::{Rewrite, Step};
+pub use crate::proposition::structure::{ModelError, Structure};
+pub use crate::proposition::term::Term;
+pub use crate::proposition::valuation::{EvaluationError, Valuation};
+pub use crate::proposition::visit::{walk, walk_mut, Fold, Visitor, VisitorMut};
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Proposition {
-    Atom(String),
+    Atom(Atom),
+    /// The constant true proposition, written `⊤` or `true`.
+    Verum,
+    /// The constant false proposition, written `⊥` or `false`.
+    Falsum,
     Negation(Box<Proposition>),
     Conditional(Box<Proposition>, Box<Proposition>),
-    Conjunction(Box<Proposition>, Box<Proposition>),
-    Disjunction(Box<Proposition>, Box<Proposition>),
+    Biconditional(Box<Proposition>, Box<Proposition>),
+    /// The conjunction of any number of conjuncts. Parsing a chain like `A & B & C` gives a
+    /// single conjunction of all three.
+    Conjunction(Vec<Proposition>),
+    /// The disjunction of any number of disjuncts, flattened in the same way as conjunctions.
+    Disjunction(Vec<Proposition>),
     Existential(String, Box<Proposition>),
     Universal(String, Box<Proposition>)
 }
 
 impl Proposition {
+    /// Return the depth to which connectives are nested in self, the same as `depth`. See
+    /// `measure` for other ways of measuring complexity.
     pub fn complexity(&self) -> usize {
-        match self {
-            Proposition::Atom(_) => 0,
-            Proposition::Negation(negatum) => 1 + negatum.complexity(),
-            Proposition::Conditional(left, right) => 1 + cmp::max(left.complexity(), right.complexity()),
-            Proposition::Conjunction(left, right) => 1 + cmp::max(left.complexity(), right.complexity()),
-            Proposition::Disjunction(left, right) => 1 + cmp::max(left.complexity(), right.complexity()),
-            Proposition::Existential(_, predicate) => 1 + predicate.complexity(),
-            Proposition::Universal(_, predicate) => 1 + predicate.complexity(),
-        }
+        self.depth()
     }
 
+    /// Return the main connective of self, or None if self is an atom or a constant, which
+    /// have no connective to decompose.
     pub fn connective(&self) -> Option<char> {
         match self {
-            Self::Atom(_) => None,
+            Self::Atom(_) | Self::Verum | Self::Falsum => None,
             Self::Negation(_) => Some('~'),
             Self::Conditional(_, _) => Some('>'),
-            Self::Conjunction(_, _) => Some('&'),
-            Self::Disjunction(_, _) => Some('v'),
+            Self::Biconditional(_, _) => Some('↔'),
+            Self::Conjunction(_) => Some('&'),
+            Self::Disjunction(_) => Some('v'),
             Self::Existential(_, _) => Some('∃'),
             Self::Universal(_, _) => Some('∀'),
         }
     }
 
     pub fn names(&self) -> Vec<String> {
-        match self {
-            Proposition::Atom(atom) => get_names(atom),
-            Proposition::Negation(negatum) => negatum.names(),
-            Proposition::Conditional(left, right) => {
-                let mut names: Vec<String> = Vec::new();
-                for name in left.names() { names.push(name); }
-                for name in right.names() { names.push(name); }
-                names
-            },
-            Proposition::Conjunction(left, right) => {
-                let mut names: Vec<String> = Vec::new();
-                for name in left.names() { names.push(name); }
-                for name in right.names() { names.push(name); }
-                names
-            },
-            Proposition::Disjunction(left, right) => {
-                let mut names: Vec<String> = Vec::new();
-                for name in left.names() { names.push(name); }
-                for name in right.names() { names.push(name); }
-                names
-            },
-            Proposition::Existential(_, predicate) => predicate.names(),
-            Proposition::Universal(_, predicate) => predicate.names()
-        }
+        self.atoms().into_iter().flat_map(|atom| atom.names()).collect()
     }
 
     pub fn variables(&self) -> Vec<String> {
-        match self {
-            Proposition::Atom(atom) => get_variables(atom),
-            Proposition::Negation(negatum) => negatum.variables(),
-            Proposition::Conditional(left, right) => {
-                let mut variables: Vec<String> = Vec::new();
-                for variable in left.variables() { variables.push(variable); }
-                for variable in right.variables() { variables.push(variable); }
-                variables
-            },
-            Proposition::Conjunction(left, right) => {
-                let mut variables: Vec<String> = Vec::new();
-                for variable in left.variables() { variables.push(variable); }
-                for variable in right.variables() { variables.push(variable); }
-                variables
-            },
-            Proposition::Disjunction(left, right) => {
-                let mut variables: Vec<String> = Vec::new();
-                for variable in left.variables() { variables.push(variable); }
-                for variable in right.variables() { variables.push(variable); }
-                variables
+        self.atoms().into_iter().flat_map(|atom| atom.variables()).collect()
+    }
+
+    pub fn instantiate(&mut self, var: &str, name: &str) {
+        self.substitute(var, &Term::Name(name.to_string()));
+    }
+
+    /// Return the variables with an occurrence in self not bound by any quantifier, in the
+    /// order they first occur, without duplicates.
+    pub fn free_variables(&self) -> Vec<String> {
+        let mut free: Vec<String> = Vec::new();
+        let candidates = match self {
+            Self::Atom(atom) => atom.variables(),
+            Self::Existential(var, predicate) | Self::Universal(var, predicate) => {
+                predicate.free_variables().into_iter().filter(|v| v != var).collect()
             },
-            Proposition::Existential(_, predicate) => predicate.variables(),
-            Proposition::Universal(_, predicate) => predicate.variables()
+            _ => self.content().into_iter().flat_map(|prop| prop.free_variables()).collect(),
+        };
+        for var in candidates {
+            if !free.contains(&var) { free.push(var); }
+        }
+        free
+    }
+
+    /// Return the variables bound by the quantifiers in self, in the order their
+    /// quantifiers occur, without duplicates.
+    pub fn bound_variables(&self) -> Vec<String> {
+        let mut bound: Vec<String> = Vec::new();
+        if let Self::Existential(var, _) | Self::Universal(var, _) = self {
+            bound.push(var.clone());
         }
+        if !matches!(self, Self::Atom(_)) {
+            for var in self.content().into_iter().flat_map(|prop| prop.bound_variables()) {
+                if !bound.contains(&var) { bound.push(var); }
+            }
+        }
+        bound
+    }
+
+    /// Whether self has no free variables.
+    pub fn is_closed(&self) -> bool {
+        self.free_variables().is_empty()
     }
 
-    pub fn instantiate(&mut self, var: &String, name: &String) {
+    /// Replace each free occurrence of the variable var in self, including those nested in
+    /// function applications, with term. Occurrences bound by a quantifier are left alone,
+    /// and quantifiers which would capture a variable in term are renamed first, so that
+    /// substituting `<y>` for `<x>` in `∀<y> Loves(<x>, <y>)` gives `∀<a> Loves(<y>, <a>)`.
+    ///
+    /// # Panics
+    /// Panics if a quantifier must be renamed but every variable from `<a>` to `<z>` is
+    /// already in use.
+    pub fn substitute(&mut self, var: &str, term: &Term) {
         match self {
-            Self::Atom(ref mut atom) => set_name(atom, var, name),
-            Self::Negation(ref mut negatum) => negatum.instantiate(var, name),
-            Self::Conditional(ref mut left, ref mut right) => {
-                left.instantiate(var, name);
-                right.instantiate(var, name);
+            Self::Atom(ref mut atom) => atom.substitute(var, term),
+            Self::Existential(ref mut bound, ref mut predicate) | Self::Universal(ref mut bound, ref mut predicate) => {
+                if bound == var || !predicate.free_variables().iter().any(|v| v == var) { return }
+                if term.variables().contains(bound) {
+                    let mut used: Vec<String> = term.variables();
+                    used.append(&mut predicate.variables());
+                    used.append(&mut predicate.bound_variables());
+                    used.push(var.to_string());
+                    let fresh = fresh_variable(&used);
+                    predicate.substitute(bound, &Term::Variable(fresh.clone()));
+                    *bound = fresh;
+                }
+                predicate.substitute(var, term);
             },
-            Self::Conjunction(ref mut left, ref mut right) => {
-                left.instantiate(var, name);
-                right.instantiate(var, name);
-            },
-            Self::Disjunction(ref mut left, ref mut right) => {
-                left.instantiate(var, name);
-                right.instantiate(var, name);
-            },
-            Self::Existential(_ , ref mut predicate) => predicate.instantiate(var, name),
-            Self::Universal(_ , ref mut predicate) => predicate.instantiate(var, name),
+            _ => for prop in self.content_mut() { prop.substitute(var, term) },
         }
     }
 
+    /// Return a copy of self with its bound variables renamed systematically, so that two
+    /// propositions which differ only in the names of their bound variables have the same
+    /// canonical form. A quantifier nested inside n others binds the (n + 1)th variable from
+    /// `<a>` to `<z>` which is not free in self, eg. `∀<y>(∃<x>(Loves(<x>, <y>)))` becomes
+    /// `∀<a>(∃<b>(Loves(<b>, <a>)))`.
+    ///
+    /// # Panics
+    /// Panics if quantifiers are nested more deeply than there are unused variables.
+    pub fn canonical(&self) -> Proposition {
+        let mut canonical = self.clone();
+        canonical.rename_bound(&self.free_variables(), 0);
+        canonical
+    }
+
+    /// Whether self and other are the same up to the renaming of bound variables.
+    pub fn alpha_equivalent(&self, other: &Proposition) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    /// Rename the bound variables in self as described in `canonical`, where self is nested
+    /// inside depth quantifiers.
+    fn rename_bound(&mut self, free: &[String], depth: usize) {
+        let depth = match self {
+            Self::Existential(ref mut bound, ref mut predicate) | Self::Universal(ref mut bound, ref mut predicate) => {
+                let name: String = ('a'..='z')
+                    .map(String::from)
+                    .filter(|var| !free.contains(var))
+                    .nth(depth)
+                    .expect("quantifiers nested too deeply to name canonically");
+                if *bound != name {
+                    predicate.substitute(bound, &Term::Variable(name.clone()));
+                    *bound = name;
+                }
+                depth + 1
+            },
+            _ => depth,
+        };
+        for prop in self.content_mut() { prop.rename_bound(free, depth); }
+    }
+
+    /// Return every term in self's atoms, including those nested in function applications.
+    pub fn terms(&self) -> Vec<&Term> {
+        self.atoms().into_iter().flat_map(|atom| atom.subterms()).collect()
+    }
+
+    /// Return the function symbols in self along with the number of arguments each takes.
+    pub fn functions(&self) -> Vec<(String, usize)> {
+        self.terms()
+            .into_iter()
+            .filter_map(|term| match term {
+                Term::Function(symbol, args) => Some((symbol.clone(), args.len())),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Return a vec containing references to the propositional content of self. Atoms return references
-    /// to themselves (and not the strings they contain.)
+    /// to themselves (and not the strings they contain.) Constants have no content.
     pub fn content(&self) -> Vec<&Proposition> {
         match self {
             // Atoms' content is just themselves. We can't reasonably return a mixed vec full of
-            // both strings and propositions, so we don't.
-            Self::Atom(_) => vec![&self],
-            // &* on each contained proposition because we want references to
+            // both terms and propositions, so we don't.
+            Self::Atom(_) => vec![self],
+            Self::Verum | Self::Falsum => Vec::new(),
+            // &** on each contained proposition because we want references to
             // the objects, not to their boxes
-            Self::Negation(negatum) => vec![&*negatum],
-            Self::Conditional(left, right) => vec![&*left, &*right],
-            Self::Conjunction(left, right) => vec![&*left, &*right],
-            Self::Disjunction(left, right) => vec![&*left, &*right],
-            Self::Existential(_, predicate) => vec![&*predicate],
-            Self::Universal(_, predicate) => vec![&*predicate],
+            Self::Negation(negatum) => vec![&**negatum],
+            Self::Conditional(left, right) => vec![&**left, &**right],
+            Self::Biconditional(left, right) => vec![&**left, &**right],
+            Self::Conjunction(conjuncts) => conjuncts.iter().collect(),
+            Self::Disjunction(disjuncts) => disjuncts.iter().collect(),
+            Self::Existential(_, predicate) => vec![&**predicate],
+            Self::Universal(_, predicate) => vec![&**predicate],
+        }
+    }
+
+    /// Merge conjunctions directly inside conjunctions, and disjunctions directly inside
+    /// disjunctions, throughout self, so that eg. `((A & B) & C)` becomes `(A & B & C)`.
+    /// Conjunctions and disjunctions of a single proposition are replaced by it.
+    pub fn flatten(&mut self) {
+        for prop in self.content_mut() { prop.flatten(); }
+        match self {
+            Self::Conjunction(conjuncts) => {
+                *conjuncts = std::mem::take(conjuncts).into_iter()
+                    .flat_map(|prop| match prop {
+                        Self::Conjunction(inner) => inner,
+                        prop => vec![prop],
+                    })
+                    .collect();
+                if conjuncts.len() == 1 { *self = conjuncts.remove(0) }
+            },
+            Self::Disjunction(disjuncts) => {
+                *disjuncts = std::mem::take(disjuncts).into_iter()
+                    .flat_map(|prop| match prop {
+                        Self::Disjunction(inner) => inner,
+                        prop => vec![prop],
+                    })
+                    .collect();
+                if disjuncts.len() == 1 { *self = disjuncts.remove(0) }
+            },
+            _ => {},
+        }
+    }
+
+    /// Return mutable references to the propositional content of self. Unlike `content`,
+    /// atoms have no content here.
+    fn content_mut(&mut self) -> Vec<&mut Proposition> {
+        match self {
+            Self::Atom(_) | Self::Verum | Self::Falsum => Vec::new(),
+            Self::Negation(negatum) => vec![&mut **negatum],
+            Self::Conditional(left, right) => vec![&mut **left, &mut **right],
+            Self::Biconditional(left, right) => vec![&mut **left, &mut **right],
+            Self::Conjunction(conjuncts) => conjuncts.iter_mut().collect(),
+            Self::Disjunction(disjuncts) => disjuncts.iter_mut().collect(),
+            Self::Existential(_, predicate) => vec![&mut **predicate],
+            Self::Universal(_, predicate) => vec![&mut **predicate],
         }
     }
 
-    pub fn from_str(s: &str) -> Proposition {
-        proposition_from_string(String::from(s)).unwrap()
+    /// Parse s into a proposition, along with the byte spans in s of the proposition and
+    /// each of its subformulas. Unparenthesized formulas are grouped with `~` binding most
+    /// tightly, then `&`, `v`, `>` and finally `<>`. Chains of `&` or `v` give a single
+    /// n-ary conjunction or disjunction, and `>` and `<>` associate to the right.
+    pub fn parse_spanned(s: &str) -> Result<(Proposition, SpanTree), PropositionCreationError> {
+        proposition_with_spans(s)
+    }
+
+    /// Parse each non-blank line of s as a proposition. Unlike `from_str`, this does not stop
+    /// at the first malformed line, and returns an error for each one.
+    pub fn from_lines(s: &str) -> Result<Vec<Proposition>, Vec<PropositionCreationError>> {
+        propositions_from_lines(s)
     }
 }
 
+/// Return the first variable from `<a>` to `<z>` which is not in used.
+///
+/// # Panics
+/// Panics if every one of them is in use.
+fn fresh_variable(used: &[String]) -> String {
+    ('a'..='z')
+        .map(String::from)
+        .find(|var| !used.contains(var))
+        .expect("no unused variable between <a> and <z>")
+}
+
+impl FromStr for Proposition {
+    type Err = PropositionCreationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        proposition_from_string(String::from(s))
+    }
+}
+
+/// The byte spans of a parsed proposition and of each of its subformulas. Children are
+/// in the same order as the propositions returned by `Proposition::content`, except that
+/// atoms have no children.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpanTree {
+    pub span: Span,
+    pub children: Vec<SpanTree>,
+}
+
 impl Clone for Proposition {
     fn clone(&self) -> Self {
         match self {
-            Proposition::Atom(atom) => Proposition::Atom(String::from(atom)),
+            Proposition::Atom(atom) => Proposition::Atom(atom.clone()),
+            Proposition::Verum => Proposition::Verum,
+            Proposition::Falsum => Proposition::Falsum,
             Proposition::Negation(negatum) => Proposition::Negation(negatum.clone()),
             Proposition::Conditional(left, right) => Proposition::Conditional(left.clone(), right.clone()),
-            Proposition::Conjunction(left, right) => Proposition::Conjunction(left.clone(), right.clone()),
-            Proposition::Disjunction(left, right) => Proposition::Disjunction(left.clone(), right.clone()),
+            Proposition::Biconditional(left, right) => Proposition::Biconditional(left.clone(), right.clone()),
+            Proposition::Conjunction(conjuncts) => Proposition::Conjunction(conjuncts.clone()),
+            Proposition::Disjunction(disjuncts) => Proposition::Disjunction(disjuncts.clone()),
             Proposition::Existential(var, predicate) => Proposition::Existential(String::from(var), predicate.clone()),
             Proposition::Universal(var, predicate) => Proposition::Universal(String::from(var), predicate.clone()),
         }
@@ -155,49 +327,35 @@ impl Clone for Proposition {
 }
 
 impl Display for Proposition {
+    /// Propositions are displayed so that they parse back to themselves, except that an empty
+    /// conjunction, which has no syntax of its own, is displayed as the `⊤` it is equivalent
+    /// to, and an empty disjunction as `⊥`.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            Proposition::Conjunction(conjuncts) if conjuncts.is_empty() => write!(f, "⊤"),
+            Proposition::Disjunction(disjuncts) if disjuncts.is_empty() => write!(f, "⊥"),
             Proposition::Atom(atom) => write!(f, "{}", atom),
+            Proposition::Verum => write!(f, "⊤"),
+            Proposition::Falsum => write!(f, "⊥"),
             Proposition::Negation(negatum) => write!(f, "~({})", negatum),
             Proposition::Conditional(left, right) => write!(f, "({} > {})", left, right),
-            Proposition::Conjunction(left, right) => write!(f, "({} & {})", left, right),
-            Proposition::Disjunction(left, right) => write!(f, "({} v {})", left, right),
-            Proposition::Existential(var, predicate) => write!(f, "∃{}({})", var, predicate),
-            Proposition::Universal(var, predicate) => write!(f, "∀{}({})", var, predicate),
+            Proposition::Biconditional(left, right) => write!(f, "({} <> {})", left, right),
+            Proposition::Conjunction(conjuncts) => write!(f, "({})", conjuncts.iter().join(" & ")),
+            Proposition::Disjunction(disjuncts) => write!(f, "({})", disjuncts.iter().join(" v ")),
+            Proposition::Existential(var, predicate) => write!(f, "∃<{}>({})", var, predicate),
+            Proposition::Universal(var, predicate) => write!(f, "∀<{}>({})", var, predicate),
         }
     }
 }
 
-fn get_variables(string: &String) -> Vec<String> {
-    let mut variables: Vec<String> = Vec::new();
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"\<([a-z]{1})\>").unwrap();
-    }
-    for capture in RE.captures_iter(string) {
-        variables.push(capture[1].to_string());    }
-    variables
-}
-
-fn get_names(string: &String) -> Vec<String> {
-    let mut names: Vec<String> = Vec::new();
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"\<([a-z]{2,})\>").unwrap();
-    }
-    for capture in RE.captures_iter(string) {
-        names.push(capture[1].to_string());    }
-    names
-}
-
-fn set_name(string: &mut String, var: &String, name: &String) {
-    let name_re = Regex::new(format!("<({var})>").as_str()).unwrap();
-    *string = name_re.replace_all(string.as_str(), format!("<{name}>")).to_string();
-}
-
 #[derive(Debug)]
 pub enum PropositionType {
     Atom,
+    Verum,
+    Falsum,
     Negation,
     Conditional,
+    Biconditional,
     Conjunction,
     Disjunction,
     Existential,
@@ -207,24 +365,25 @@ pub enum PropositionType {
 
 #[cfg(test)]
 mod test {
-    use crate::proposition::Proposition;
+    use std::str::FromStr;
+    use crate::proposition::{Atom, Proposition, Term};
 
     #[test]
     fn test_atomic_instantiate() {
         let string = String::from("<a> is on the mat");
-        let mut atom = Proposition::Atom(string);
+        let mut atom = Proposition::Atom(Atom::prose(&string));
         let var = "a".to_string();
         let name = "kitty".to_string();
 
         atom.instantiate(&var, &name);
 
-        assert_eq!(atom.content(), vec![&Proposition::Atom(String::from("<kitty> is on the mat"))]);
+        assert_eq!(atom.content(), vec![&Proposition::Atom(Atom::prose("<kitty> is on the mat"))]);
     }
 
     #[test]
     fn test_negation_instantiate() {
         let string: String = String::from("<a> is on the mat");
-        let atom = Proposition::Atom(string);
+        let atom = Proposition::Atom(Atom::prose(&string));
         let mut negation = Proposition::Negation(Box::new(atom));
         let var = "a".to_string();
         let name = "kitty".to_string();
@@ -232,7 +391,7 @@ mod test {
         negation.instantiate(&var, &name);
 
         assert_eq!(negation.content(),
-                   vec![&Proposition::Atom(String::from("<kitty> is on the mat"))]
+                   vec![&Proposition::Atom(Atom::prose("<kitty> is on the mat"))]
         )
     }
 
@@ -241,8 +400,8 @@ mod test {
         let left = String::from("<a> is a cat");
         let right = String::from("<a> is on the mat");
 
-        let left = Proposition::Atom(left);
-        let right = Proposition::Atom(right);
+        let left = Proposition::Atom(Atom::prose(&left));
+        let right = Proposition::Atom(Atom::prose(&right));
 
         let mut conditional = Proposition::Conditional(
             Box::new(left),
@@ -257,24 +416,34 @@ mod test {
         assert_eq!(
             conditional.content(),
             vec![
-                &Proposition::Atom(String::from("<kitty> is a cat")),
-                &Proposition::Atom(String::from("<kitty> is on the mat"))
+                &Proposition::Atom(Atom::prose("<kitty> is a cat")),
+                &Proposition::Atom(Atom::prose("<kitty> is on the mat"))
             ]
         )
     }
 
+    #[test]
+    fn test_biconditional_instantiate() {
+        let mut biconditional: Proposition = "<a> is a cat <> ~(<a> is a dog)".parse().unwrap();
+        assert_eq!(biconditional.complexity(), 2);
+        assert_eq!(biconditional.connective(), Some('↔'));
+        assert_eq!(Proposition::Verum.connective(), None);
+        assert_eq!(Proposition::Falsum.connective(), None);
+
+        biconditional.instantiate("a", "kitty");
+
+        assert_eq!(biconditional.to_string(), "(<kitty> is a cat <> ~(<kitty> is a dog))");
+    }
+
     #[test]
     fn test_conjunction_instantiate() {
         let left = String::from("<a> is a cat");
         let right = String::from("<a> is on the mat");
 
-        let left = Proposition::Atom(left);
-        let right = Proposition::Atom(right);
+        let left = Proposition::Atom(Atom::prose(&left));
+        let right = Proposition::Atom(Atom::prose(&right));
 
-        let mut conjunction = Proposition::Conjunction(
-            Box::new(left),
-            Box::new(right)
-        );
+        let mut conjunction = Proposition::Conjunction(vec![left, right]);
 
         let var = "a".to_string();
         let name = "kitty".to_string();
@@ -284,8 +453,8 @@ mod test {
         assert_eq!(
             conjunction.content(),
             vec![
-                &Proposition::Atom(String::from("<kitty> is a cat")),
-                &Proposition::Atom(String::from("<kitty> is on the mat"))
+                &Proposition::Atom(Atom::prose("<kitty> is a cat")),
+                &Proposition::Atom(Atom::prose("<kitty> is on the mat"))
             ]
         )
     }
@@ -295,13 +464,10 @@ mod test {
         let left = String::from("<a> is a cat");
         let right = String::from("<a> is on the mat");
 
-        let left = Proposition::Atom(left);
-        let right = Proposition::Atom(right);
+        let left = Proposition::Atom(Atom::prose(&left));
+        let right = Proposition::Atom(Atom::prose(&right));
 
-        let mut disjunction = Proposition::Disjunction(
-            Box::new(left),
-            Box::new(right)
-        );
+        let mut disjunction = Proposition::Disjunction(vec![left, right]);
 
         let var = "a".to_string();
         let name = "kitty".to_string();
@@ -311,21 +477,21 @@ mod test {
         assert_eq!(
             disjunction.content(),
             vec![
-                &Proposition::Atom(String::from("<kitty> is a cat")),
-                &Proposition::Atom(String::from("<kitty> is on the mat"))
+                &Proposition::Atom(Atom::prose("<kitty> is a cat")),
+                &Proposition::Atom(Atom::prose("<kitty> is on the mat"))
             ]
         )
     }
 
     #[test]
     fn test_instantiate_existential() {
-        let predicate = String::from("<a> is on <bb>");
-        let atom = Proposition::Atom(predicate);
+        let predicate = String::from("<a> is on <b>");
+        let atom = Proposition::Atom(Atom::prose(&predicate));
         let mut existential = Proposition::Existential(
             String::from("a"), Box::new(atom)
         );
 
-        let var = "bb".to_string();
+        let var = "b".to_string();
         let name = "the mat".to_string();
 
         existential.instantiate(&var, &name);
@@ -333,7 +499,7 @@ mod test {
         assert_eq!(
             existential.content(),
             vec![
-                &Proposition::Atom(String::from("<a> is on <the mat>"))
+                &Proposition::Atom(Atom::prose("<a> is on <the mat>"))
             ]
         )
     }
@@ -342,7 +508,7 @@ mod test {
     #[test]
     fn test_instantiate_universal() {
         let predicate = String::from("<a> is on <b>");
-        let atom = Proposition::Atom(predicate);
+        let atom = Proposition::Atom(Atom::prose(&predicate));
         let mut universal = Proposition::Universal(
             String::from("a"), Box::new(atom)
         );
@@ -355,8 +521,84 @@ mod test {
         assert_eq!(
             universal.content(),
             vec![
-                &Proposition::Atom(String::from("<a> is on <the mat>"))
+                &Proposition::Atom(Atom::prose("<a> is on <the mat>"))
             ]
         )
     }
+
+    #[test]
+    fn test_from_str() {
+        let parsed: Proposition = "~A".parse().unwrap();
+        assert_eq!(parsed, Proposition::Negation(Box::new(Proposition::Atom(Atom::prose("A")))));
+        assert!("~".parse::<Proposition>().is_err());
+    }
+
+    #[test]
+    fn test_substitute_nested_terms() {
+        let mut universal = Proposition::from_str("∀<y>(Loves(mother(<x>), <y>) & <x> is a cat)").unwrap();
+        let father = Term::Function("father".to_string(), vec![Term::Name("kitty".to_string())]);
+        universal.substitute("x", &father);
+        assert_eq!(
+            universal.to_string(),
+            "∀<y>((Loves(mother(father(<kitty>)), <y>) & <father(<kitty>)> is a cat))"
+        );
+        assert_eq!(universal.functions(), vec![("mother".to_string(), 1), ("father".to_string(), 1), ("father".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_free_and_bound_variables() {
+        let proposition = Proposition::from_str("∀<x>(Loves(<x>, <y>) & ∃<z> Loves(<z>, f(<x>))) v Cat(<x>)").unwrap();
+        assert_eq!(proposition.free_variables(), vec!["y".to_string(), "x".to_string()]);
+        assert_eq!(proposition.bound_variables(), vec!["x".to_string(), "z".to_string()]);
+        assert!(!proposition.is_closed());
+        assert!(Proposition::from_str("∀<x> ∃<y> Loves(<x>, <y>)").unwrap().is_closed());
+        assert!(Proposition::from_str("⊤ & Cat(<kitty>)").unwrap().is_closed());
+    }
+
+    #[test]
+    fn test_substitute_skips_bound_occurrences() {
+        let mut proposition = Proposition::from_str("Cat(<x>) & ∃<x> Dog(<x>)").unwrap();
+        proposition.instantiate("x", "kitty");
+        assert_eq!(proposition.to_string(), "(Cat(<kitty>) & ∃<x>(Dog(<x>)))");
+    }
+
+    #[test]
+    fn test_substitute_avoids_capture() {
+        let mut proposition = Proposition::from_str("∀<y> Loves(<x>, <y>)").unwrap();
+        proposition.substitute("x", &Term::Variable("y".to_string()));
+        assert_eq!(proposition.to_string(), "∀<a>(Loves(<y>, <a>))");
+        assert_eq!(proposition.free_variables(), vec!["y".to_string()]);
+
+        // the fresh variable avoids every variable already in use
+        let mut proposition = Proposition::from_str("∀<a> (Loves(<x>, <a>) & ∃<b> Loves(<b>, f(<a>)))").unwrap();
+        proposition.substitute("x", &Term::Function("f".to_string(), vec![Term::Variable("a".to_string())]));
+        assert_eq!(proposition.to_string(), "∀<c>((Loves(f(<a>), <c>) & ∃<b>(Loves(<b>, f(<c>)))))");
+
+        // nothing is renamed when var does not occur free
+        let mut proposition = Proposition::from_str("∀<y> Loves(<z>, <y>)").unwrap();
+        proposition.substitute("x", &Term::Variable("y".to_string()));
+        assert_eq!(proposition.to_string(), "∀<y>(Loves(<z>, <y>))");
+    }
+
+    #[test]
+    fn test_alpha_equivalence() {
+        let x = Proposition::from_str("∀<x> P(<x>)").unwrap();
+        let y = Proposition::from_str("∀<y> P(<y>)").unwrap();
+        assert_ne!(x, y);
+        assert!(x.alpha_equivalent(&y));
+        assert_eq!(x.canonical().to_string(), "∀<a>(P(<a>))");
+
+        // free variables are not renamed, and canonical names avoid them
+        let free_a = Proposition::from_str("∀<x> Loves(<x>, <a>)").unwrap();
+        let free_b = Proposition::from_str("∀<x> Loves(<x>, <b>)").unwrap();
+        assert!(!free_a.alpha_equivalent(&free_b));
+        assert_eq!(free_a.canonical().to_string(), "∀<b>(Loves(<b>, <a>))");
+
+        let nested = Proposition::from_str("∀<y> ∃<x> Loves(<x>, <y>) & ∃<z> P(<z>)").unwrap();
+        assert_eq!(nested.canonical().to_string(), "(∀<a>(∃<b>(Loves(<b>, <a>))) & ∃<a>(P(<a>)))");
+        let swapped = Proposition::from_str("∀<x> ∃<y> Loves(<y>, <x>) & ∃<x> P(<x>)").unwrap();
+        assert!(nested.alpha_equivalent(&swapped));
+        let different = Proposition::from_str("∀<x> ∃<y> Loves(<x>, <y>) & ∃<x> P(<x>)").unwrap();
+        assert!(!nested.alpha_equivalent(&different));
+    }
 }
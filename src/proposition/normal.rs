@@ -0,0 +1,511 @@
+use itertools::Itertools;
+use crate::proposition::atom::Atom;
+use crate::proposition::term::Term;
+use crate::proposition::{fresh_variable, Proposition};
+
+/// A conjunction of disjunctions, or a disjunction of conjunctions, as a list of lists of
+/// literals.
+type Clauses = Vec<Vec<Proposition>>;
+
+/// A constructor for an n-ary connective, eg. `Proposition::Conjunction`.
+type Join = fn(Vec<Proposition>) -> Proposition;
+
+/// The kinds of quantifier, for describing the quantifier prefix of a formula.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Quantifier {
+    Existential,
+    Universal,
+}
+
+impl Proposition {
+    /// Return self in negation normal form: conditionals and biconditionals are rewritten
+    /// with `~`, `&` and `v`, and negations are pushed inwards until they only apply to atoms.
+    /// Quantifiers are kept, with `~∀` becoming `∃~` and `~∃` becoming `∀~`.
+    pub fn nnf(&self) -> Proposition {
+        let mut nnf = positive(self);
+        nnf.flatten();
+        nnf
+    }
+
+    /// Return self in conjunctive normal form, a conjunction of disjunctions of literals,
+    /// by distributing disjunctions over conjunctions. Quantified formulas are treated as
+    /// literals, with their bodies converted in turn. The result is equivalent to self, but
+    /// may be exponentially larger; see `tseitin_cnf` for a linear alternative.
+    pub fn cnf(&self) -> Proposition {
+        from_clauses(clauses(&self.nnf(), true), true)
+    }
+
+    /// Return self in disjunctive normal form, a disjunction of conjunctions of literals.
+    /// Quantified formulas are treated as in `cnf`.
+    pub fn dnf(&self) -> Proposition {
+        from_clauses(clauses(&self.nnf(), false), false)
+    }
+
+    /// Return a formula in conjunctive normal form which is satisfiable exactly when self is,
+    /// and which grows linearly with self. Each conjunction and disjunction in self's
+    /// negation normal form is named by a new atom (`t1`, `t2` and so on, skipping any that
+    /// already appear in self), with clauses stating that the atom is equivalent to what it
+    /// names. Quantified formulas are treated as literals.
+    pub fn tseitin_cnf(&self) -> Proposition {
+        let nnf = self.nnf();
+        let mut tseitin = Tseitin { taken: nnf.atoms().into_iter().cloned().collect(), clauses: Vec::new(), count: 0 };
+        let root = tseitin.name(&nnf);
+        tseitin.clauses.push(vec![root]);
+        from_clauses(tseitin.clauses, true)
+    }
+
+    /// Return self in prenex normal form: a string of quantifiers followed by a
+    /// quantifier-free matrix in negation normal form. Bound variables are renamed first so
+    /// that no two quantifiers bind the same variable and none binds a free variable, which
+    /// lets every quantifier be moved to the front. Quantifiers keep their left to right
+    /// order. As usual, this assumes the domain is not empty.
+    ///
+    /// # Panics
+    /// Panics if self has more quantifiers and free variables than there are variables
+    /// from `<a>` to `<z>`.
+    pub fn prenex(&self) -> Proposition {
+        let mut nnf = self.nnf();
+        nnf.rename_apart(&mut self.free_variables());
+        let (prefix, mut matrix) = pull_quantifiers(nnf);
+        matrix.flatten();
+        with_prefix(prefix, matrix)
+    }
+
+    /// Return a formula in prenex form with only universal quantifiers which is satisfiable
+    /// exactly when self is. Each existential in self's prenex form is removed, and its
+    /// variable replaced by a new function (`sk1(<x>, ...)`) of self's free variables and
+    /// the universals before it, or by a new name (`<sk1>`, `<sk2>` and so on) if there are
+    /// none. Free variables count as universally quantified outside everything else, so
+    /// they come first and stay free in the result. Names and function symbols already in
+    /// self are not reused.
+    pub fn skolemize(&self) -> Proposition {
+        let (prefix, mut matrix) = pull_quantifiers(self.prenex());
+        let free = self.free_variables();
+        let names = self.names();
+        let symbols: Vec<String> = self.functions().into_iter().map(|(symbol, _)| symbol).collect();
+        let mut count: usize = 0;
+        let mut universals: Vec<(Quantifier, String)> = Vec::new();
+        for (quantifier, var) in prefix {
+            if quantifier == Quantifier::Universal {
+                universals.push((quantifier, var));
+                continue;
+            }
+            let symbol = loop {
+                count += 1;
+                let symbol = format!("sk{count}");
+                if !names.contains(&symbol) && !symbols.contains(&symbol) { break symbol }
+            };
+            let args: Vec<Term> = free.iter()
+                .chain(universals.iter().map(|(_, var)| var))
+                .map(|var| Term::Variable(var.clone()))
+                .collect();
+            let term = match args.is_empty() {
+                true => Term::Name(symbol),
+                false => Term::Function(symbol, args),
+            };
+            matrix.substitute(&var, &term);
+        }
+        with_prefix(universals, matrix)
+    }
+
+    /// Return the kinds of the quantifiers at the front of self, from the outside in.
+    pub fn quantifier_prefix(&self) -> Vec<Quantifier> {
+        match self {
+            Proposition::Existential(_, predicate) => {
+                let mut prefix = vec![Quantifier::Existential];
+                prefix.append(&mut predicate.quantifier_prefix());
+                prefix
+            },
+            Proposition::Universal(_, predicate) => {
+                let mut prefix = vec![Quantifier::Universal];
+                prefix.append(&mut predicate.quantifier_prefix());
+                prefix
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rename bound variables so that no two quantifiers in self bind the same variable, and
+    /// none binds a variable in used, adding each variable bound to used.
+    fn rename_apart(&mut self, used: &mut Vec<String>) {
+        if let Proposition::Existential(bound, predicate) | Proposition::Universal(bound, predicate) = self {
+            if used.contains(bound) {
+                let mut avoid = used.clone();
+                avoid.append(&mut predicate.variables());
+                let fresh = fresh_variable(&avoid);
+                predicate.substitute(bound, &Term::Variable(fresh.clone()));
+                *bound = fresh;
+            }
+            used.push(bound.clone());
+        }
+        for prop in self.content_mut() { prop.rename_apart(used) }
+    }
+
+    /// Whether self is in negation normal form.
+    pub fn is_nnf(&self) -> bool {
+        match self {
+            Proposition::Negation(negatum) => matches!(**negatum, Proposition::Atom(_)),
+            Proposition::Conditional(_, _) | Proposition::Biconditional(_, _) => false,
+            Proposition::Atom(_) => true,
+            _ => self.content().into_iter().all(|prop| prop.is_nnf()),
+        }
+    }
+
+    /// Whether self is a conjunction of disjunctions of literals, where a lone disjunct or
+    /// conjunct may stand for a disjunction or conjunction of one.
+    pub fn is_cnf(&self) -> bool {
+        match self {
+            Proposition::Conjunction(conjuncts) => conjuncts.iter().all(is_clause),
+            _ => is_clause(self),
+        }
+    }
+
+    /// Whether self is a disjunction of conjunctions of literals.
+    pub fn is_dnf(&self) -> bool {
+        match self {
+            Proposition::Disjunction(disjuncts) => disjuncts.iter().all(is_term),
+            _ => is_term(self),
+        }
+    }
+}
+
+/// Return the negation normal form of proposition.
+fn positive(proposition: &Proposition) -> Proposition {
+    match proposition {
+        Proposition::Atom(_) | Proposition::Verum | Proposition::Falsum => proposition.clone(),
+        Proposition::Negation(negatum) => negative(negatum),
+        Proposition::Conditional(left, right) => Proposition::Disjunction(vec![negative(left), positive(right)]),
+        Proposition::Biconditional(left, right) => Proposition::Conjunction(vec![
+            Proposition::Disjunction(vec![negative(left), positive(right)]),
+            Proposition::Disjunction(vec![positive(left), negative(right)]),
+        ]),
+        Proposition::Conjunction(conjuncts) => Proposition::Conjunction(conjuncts.iter().map(positive).collect()),
+        Proposition::Disjunction(disjuncts) => Proposition::Disjunction(disjuncts.iter().map(positive).collect()),
+        Proposition::Existential(var, predicate) => Proposition::Existential(var.clone(), Box::new(positive(predicate))),
+        Proposition::Universal(var, predicate) => Proposition::Universal(var.clone(), Box::new(positive(predicate))),
+    }
+}
+
+/// Return the negation normal form of the negation of proposition.
+fn negative(proposition: &Proposition) -> Proposition {
+    match proposition {
+        Proposition::Atom(_) => Proposition::Negation(Box::new(proposition.clone())),
+        Proposition::Verum => Proposition::Falsum,
+        Proposition::Falsum => Proposition::Verum,
+        Proposition::Negation(negatum) => positive(negatum),
+        Proposition::Conditional(left, right) => Proposition::Conjunction(vec![positive(left), negative(right)]),
+        Proposition::Biconditional(left, right) => Proposition::Disjunction(vec![
+            Proposition::Conjunction(vec![positive(left), negative(right)]),
+            Proposition::Conjunction(vec![negative(left), positive(right)]),
+        ]),
+        Proposition::Conjunction(conjuncts) => Proposition::Disjunction(conjuncts.iter().map(negative).collect()),
+        Proposition::Disjunction(disjuncts) => Proposition::Conjunction(disjuncts.iter().map(negative).collect()),
+        Proposition::Existential(var, predicate) => Proposition::Universal(var.clone(), Box::new(negative(predicate))),
+        Proposition::Universal(var, predicate) => Proposition::Existential(var.clone(), Box::new(negative(predicate))),
+    }
+}
+
+/// Return the clauses of nnf, which must be in negation normal form: its CNF clauses if cnf
+/// is true, or its DNF terms otherwise.
+fn clauses(nnf: &Proposition, cnf: bool) -> Clauses {
+    // the connective whose operands' clauses are simply collected, and the one which must
+    // be distributed over it
+    let (collected, distributed) = match (nnf, cnf) {
+        (Proposition::Conjunction(props), true) | (Proposition::Disjunction(props), false) => (Some(props), None),
+        (Proposition::Disjunction(props), true) | (Proposition::Conjunction(props), false) => (None, Some(props)),
+        _ => (None, None),
+    };
+    if let Some(props) = collected {
+        return props.iter().flat_map(|prop| clauses(prop, cnf)).collect()
+    }
+    if let Some(props) = distributed {
+        // the product of no operands' clauses is empty, but distributing over nothing
+        // should leave the one empty clause: `⊥` in CNF, `⊤` in DNF
+        if props.is_empty() { return vec![Vec::new()] }
+        return props.iter()
+            .map(|prop| clauses(prop, cnf))
+            .multi_cartesian_product()
+            .map(|choice| choice.into_iter().flatten().unique().collect())
+            .collect()
+    }
+    match (nnf, cnf) {
+        // the empty conjunction is true and the empty disjunction false
+        (Proposition::Verum, true) | (Proposition::Falsum, false) => Vec::new(),
+        (Proposition::Falsum, true) | (Proposition::Verum, false) => vec![Vec::new()],
+        (Proposition::Existential(var, predicate), _) => {
+            let body = from_clauses(clauses(predicate, cnf), cnf);
+            vec![vec![Proposition::Existential(var.clone(), Box::new(body))]]
+        },
+        (Proposition::Universal(var, predicate), _) => {
+            let body = from_clauses(clauses(predicate, cnf), cnf);
+            vec![vec![Proposition::Universal(var.clone(), Box::new(body))]]
+        },
+        (literal, _) => vec![vec![literal.clone()]],
+    }
+}
+
+/// Build a formula from CNF clauses if cnf is true, or from DNF terms otherwise.
+fn from_clauses(clauses: Clauses, cnf: bool) -> Proposition {
+    let (outer, inner): (Join, Join) = match cnf {
+        true => (Proposition::Conjunction, Proposition::Disjunction),
+        false => (Proposition::Disjunction, Proposition::Conjunction),
+    };
+    let mut joined = outer(clauses.into_iter().map(inner).collect());
+    // flattening removes joins of one proposition, leaving joins of none
+    joined.flatten();
+    replace_empty(&mut joined);
+    joined
+}
+
+/// Replace empty conjunctions with `⊤` and empty disjunctions with `⊥`, at the top of
+/// proposition and among its operands.
+fn replace_empty(proposition: &mut Proposition) {
+    if let Proposition::Conjunction(props) | Proposition::Disjunction(props) = proposition {
+        for prop in props.iter_mut() { replace_empty(prop) }
+    }
+    match proposition {
+        Proposition::Conjunction(props) if props.is_empty() => *proposition = Proposition::Verum,
+        Proposition::Disjunction(props) if props.is_empty() => *proposition = Proposition::Falsum,
+        _ => {},
+    }
+}
+
+/// Whether proposition is a literal: an atom, constant or quantified formula, or the
+/// negation of one.
+fn is_literal(proposition: &Proposition) -> bool {
+    match proposition {
+        Proposition::Negation(negatum) => !matches!(**negatum, Proposition::Negation(_)) && is_literal(negatum),
+        Proposition::Atom(_) | Proposition::Verum | Proposition::Falsum => true,
+        Proposition::Existential(_, _) | Proposition::Universal(_, _) => true,
+        _ => false,
+    }
+}
+
+fn is_clause(proposition: &Proposition) -> bool {
+    match proposition {
+        Proposition::Disjunction(disjuncts) => disjuncts.iter().all(is_literal),
+        _ => is_literal(proposition),
+    }
+}
+
+fn is_term(proposition: &Proposition) -> bool {
+    match proposition {
+        Proposition::Conjunction(conjuncts) => conjuncts.iter().all(is_literal),
+        _ => is_literal(proposition),
+    }
+}
+
+/// The state of a Tseitin transformation: the atoms which may not be used as new names,
+/// the clauses so far, and the number of names made.
+struct Tseitin {
+    taken: Vec<Atom>,
+    clauses: Clauses,
+    count: usize,
+}
+
+impl Tseitin {
+    /// Return a literal equivalent to nnf, given the clauses added so far.
+    fn name(&mut self, nnf: &Proposition) -> Proposition {
+        let (props, conjunction) = match nnf {
+            Proposition::Conjunction(conjuncts) => (conjuncts, true),
+            Proposition::Disjunction(disjuncts) => (disjuncts, false),
+            literal => return literal.clone(),
+        };
+        let literals: Vec<Proposition> = props.iter().map(|prop| self.name(prop)).collect();
+        let name = self.fresh();
+        let not_name = Proposition::Negation(Box::new(name.clone()));
+        if conjunction {
+            // name > each conjunct, and the conjuncts together > name
+            for literal in literals.iter() {
+                self.clauses.push(vec![not_name.clone(), literal.clone()]);
+            }
+            let mut clause = vec![name.clone()];
+            clause.extend(literals.iter().map(negate));
+            self.clauses.push(clause);
+        } else {
+            // name > some disjunct, and each disjunct > name
+            let mut clause = vec![not_name.clone()];
+            clause.extend(literals.iter().cloned());
+            self.clauses.push(clause);
+            for literal in literals.iter() {
+                self.clauses.push(vec![negate(literal), name.clone()]);
+            }
+        }
+        name
+    }
+
+    /// Return a new atom which does not appear in the formula being transformed.
+    fn fresh(&mut self) -> Proposition {
+        loop {
+            self.count += 1;
+            let atom = Atom::prose(&format!("t{}", self.count));
+            if !self.taken.contains(&atom) { return Proposition::Atom(atom) }
+        }
+    }
+}
+
+/// Return the literal which is the negation of literal.
+fn negate(literal: &Proposition) -> Proposition {
+    match literal {
+        Proposition::Negation(negatum) => (**negatum).clone(),
+        Proposition::Verum => Proposition::Falsum,
+        Proposition::Falsum => Proposition::Verum,
+        _ => Proposition::Negation(Box::new(literal.clone())),
+    }
+}
+
+/// Split a formula in negation normal form whose quantifiers bind distinct variables into
+/// its quantifiers, in left to right order, and the matrix left when they are removed.
+fn pull_quantifiers(nnf: Proposition) -> (Vec<(Quantifier, String)>, Proposition) {
+    let mut prefix: Vec<(Quantifier, String)> = Vec::new();
+    let mut pull_each = |props: Vec<Proposition>| -> Vec<Proposition> {
+        props.into_iter()
+            .map(|prop| {
+                let (mut inner, matrix) = pull_quantifiers(prop);
+                prefix.append(&mut inner);
+                matrix
+            })
+            .collect()
+    };
+    let matrix = match nnf {
+        Proposition::Existential(var, predicate) => {
+            let (mut inner, matrix) = pull_quantifiers(*predicate);
+            prefix.push((Quantifier::Existential, var));
+            prefix.append(&mut inner);
+            matrix
+        },
+        Proposition::Universal(var, predicate) => {
+            let (mut inner, matrix) = pull_quantifiers(*predicate);
+            prefix.push((Quantifier::Universal, var));
+            prefix.append(&mut inner);
+            matrix
+        },
+        Proposition::Conjunction(conjuncts) => Proposition::Conjunction(pull_each(conjuncts)),
+        Proposition::Disjunction(disjuncts) => Proposition::Disjunction(pull_each(disjuncts)),
+        literal => literal,
+    };
+    (prefix, matrix)
+}
+
+/// Wrap matrix in the quantifiers of prefix, the first outermost.
+fn with_prefix(prefix: Vec<(Quantifier, String)>, matrix: Proposition) -> Proposition {
+    prefix.into_iter().rev().fold(matrix, |body, (quantifier, var)| match quantifier {
+        Quantifier::Existential => Proposition::Existential(var, Box::new(body)),
+        Quantifier::Universal => Proposition::Universal(var, Box::new(body)),
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+    use crate::proposition::normal::Quantifier;
+    use crate::proposition::{Atom, Proposition, Valuation};
+
+    const SOURCES: [&str; 6] = [
+        "A > B",
+        "~(A <> B) v C",
+        "(A & B) v (C & D) v ~(E > A)",
+        "~(A v ~B & (C > ⊥)) & ⊤",
+        "A <> (B <> C)",
+        "~~A & (B v ⊥)",
+    ];
+
+    fn assert_equivalent(original: &Proposition, converted: &Proposition) {
+        let atoms: Vec<Atom> = original.atoms().into_iter().chain(converted.atoms()).cloned().collect();
+        for valuation in Valuation::all(&atoms) {
+            assert_eq!(original.evaluate(&valuation), converted.evaluate(&valuation), "{original} and {converted}");
+        }
+    }
+
+    #[test]
+    fn test_nnf() {
+        for source in SOURCES {
+            let proposition: Proposition = source.parse().unwrap();
+            let nnf = proposition.nnf();
+            assert!(nnf.is_nnf(), "{nnf}");
+            assert_equivalent(&proposition, &nnf);
+        }
+        let proposition: Proposition = "~∀<x> (P(<x>) > ∃<y> Q(<y>))".parse().unwrap();
+        assert_eq!(proposition.nnf().to_string(), "∃<x>((P(<x>) & ∀<y>(~(Q(<y>)))))");
+    }
+
+    #[test]
+    fn test_cnf_and_dnf() {
+        for source in SOURCES {
+            let proposition: Proposition = source.parse().unwrap();
+            let cnf = proposition.cnf();
+            assert!(cnf.is_cnf(), "{cnf}");
+            assert_equivalent(&proposition, &cnf);
+            let dnf = proposition.dnf();
+            assert!(dnf.is_dnf(), "{dnf}");
+            assert_equivalent(&proposition, &dnf);
+        }
+        let proposition: Proposition = "(A & B) v C".parse().unwrap();
+        assert_eq!(proposition.cnf().to_string(), "((A v C) & (B v C))");
+        assert_eq!(proposition.dnf().to_string(), "((A & B) v C)");
+        assert_eq!("A v ⊤".parse::<Proposition>().unwrap().cnf(), Proposition::Verum);
+        assert_eq!("A & ⊥".parse::<Proposition>().unwrap().dnf(), Proposition::Falsum);
+
+        for empty in [Proposition::Conjunction(Vec::new()), Proposition::Disjunction(Vec::new())] {
+            assert_equivalent(&empty, &empty.cnf());
+            assert_equivalent(&empty, &empty.dnf());
+        }
+        assert_eq!(Proposition::Disjunction(Vec::new()).cnf(), Proposition::Falsum);
+        assert_eq!(Proposition::Conjunction(Vec::new()).dnf(), Proposition::Verum);
+    }
+
+    #[test]
+    fn test_prenex() {
+        let proposition: Proposition = "∀<x> P(<x>) & ~∀<x> (Q(<x>) > ∃<y> R(<x>, <y>))".parse().unwrap();
+        let prenex = proposition.prenex();
+        assert_eq!(prenex.to_string(), "∀<x>(∃<a>(∀<y>((P(<x>) & Q(<a>) & ~(R(<a>, <y>))))))");
+        assert_eq!(
+            prenex.quantifier_prefix(),
+            vec![Quantifier::Universal, Quantifier::Existential, Quantifier::Universal]
+        );
+        assert!(proposition.quantifier_prefix().is_empty());
+
+        // free variables are never captured
+        let proposition: Proposition = "∃<x> P(<x>) v Q(<a>, <x>)".parse().unwrap();
+        assert_eq!(proposition.prenex().to_string(), "∃<b>((P(<b>) v Q(<a>, <x>)))");
+    }
+
+    #[test]
+    fn test_skolemize() {
+        let proposition: Proposition = "∃<x> ∀<y> ∃<z> (Loves(<x>, <z>) & Loves(<z>, <y>))".parse().unwrap();
+        assert_eq!(proposition.skolemize().to_string(), "∀<y>((Loves(<sk1>, sk2(<y>)) & Loves(sk2(<y>), <y>)))");
+
+        // existing names and symbols are not reused
+        let proposition: Proposition = "∀<x> ∀<y> ∃<z> sk1(<z>) = <sk2>".parse().unwrap();
+        assert_eq!(proposition.skolemize().to_string(), "∀<x>(∀<y>(sk1(sk3(<x>, <y>)) = <sk2>))");
+
+        // what exists can depend on the values of free variables too
+        let proposition: Proposition = "∃<y> P(<x>, <y>)".parse().unwrap();
+        assert_eq!(proposition.skolemize().to_string(), "P(<x>, sk1(<x>))");
+        let proposition: Proposition = "∀<y> ∃<z> R(<x>, <y>, <z>)".parse().unwrap();
+        assert_eq!(proposition.skolemize().to_string(), "∀<y>(R(<x>, <y>, sk1(<x>, <y>)))");
+    }
+
+    #[test]
+    fn test_tseitin_cnf() {
+        for source in SOURCES.iter().chain(["t1 v t2 & A"].iter()) {
+            let proposition: Proposition = source.parse().unwrap();
+            let tseitin = proposition.tseitin_cnf();
+            assert!(tseitin.is_cnf(), "{tseitin}");
+
+            // every assignment to the original atoms satisfies proposition exactly when it
+            // can be extended to satisfy tseitin
+            let atoms: Vec<Atom> = proposition.atoms().into_iter().cloned().unique().collect();
+            let fresh: Vec<Atom> = tseitin.atoms().into_iter().cloned().unique().filter(|atom| !atoms.contains(atom)).collect();
+            for valuation in Valuation::all(&atoms) {
+                let extendable = Valuation::all(&fresh).any(|extension| {
+                    let mut extended = valuation.clone();
+                    extended.extend(fresh.iter().map(|atom| (atom.clone(), extension.get(atom).unwrap())));
+                    tseitin.evaluate(&extended).unwrap()
+                });
+                assert_eq!(proposition.evaluate(&valuation), Ok(extendable), "{proposition} and {tseitin}");
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use itertools::Itertools;
+use crate::proposition::Proposition;
+
+/// The position of a subformula within a proposition: the index into each successive
+/// proposition's content to follow on the way down. The empty path is the proposition
+/// itself, and eg. `1.0` in `A > ~B` is `B`.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Default)]
+pub struct Path(Vec<usize>);
+
+impl Path {
+    /// Return the path to the proposition itself.
+    pub fn root() -> Path {
+        Path::default()
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return the path to the index-th proposition in the content of the subformula at self.
+    pub fn child(&self, index: usize) -> Path {
+        let mut indices = self.0.clone();
+        indices.push(index);
+        Path(indices)
+    }
+
+    /// Return the path to the subformula whose content self is in, if self isn't the root.
+    pub fn parent(&self) -> Option<Path> {
+        let (_, indices) = self.0.split_last()?;
+        Some(Path(indices.to_vec()))
+    }
+
+    /// Whether the subformula at self contains the one at other, including when they are
+    /// the same.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.0.starts_with(&self.0)
+    }
+}
+
+impl From<Vec<usize>> for Path {
+    fn from(indices: Vec<usize>) -> Self {
+        Path(indices)
+    }
+}
+
+impl FromIterator<usize> for Path {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        Path(iter.into_iter().collect())
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_root() { return write!(f, "ε") }
+        write!(f, "{}", self.0.iter().join("."))
+    }
+}
+
+/// Whether a subformula contributes to the truth of what contains it positively, like the
+/// consequent of a conditional, or negatively, like its antecedent or anything negated.
+/// Either side of a biconditional contributes both ways.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    /// Return the opposite polarity. Both is its own opposite.
+    pub fn flip(self) -> Polarity {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    /// Return the polarity of something which occurs both with polarity self and with
+    /// polarity other.
+    pub fn combine(self, other: Polarity) -> Polarity {
+        if self == other { self } else { Polarity::Both }
+    }
+
+    /// Return the polarity of the index-th proposition in the content of proposition, if
+    /// proposition itself has polarity self.
+    fn of_child(self, proposition: &Proposition, index: usize) -> Polarity {
+        match proposition {
+            Proposition::Negation(_) => self.flip(),
+            Proposition::Conditional(_, _) if index == 0 => self.flip(),
+            Proposition::Biconditional(_, _) => Polarity::Both,
+            _ => self,
+        }
+    }
+}
+
+/// Iterator over a proposition's subformulas, returned by `Proposition::subformulas`.
+pub struct Subformulas<'a> {
+    stack: Vec<(Path, Polarity, &'a Proposition)>,
+}
+
+impl<'a> Iterator for Subformulas<'a> {
+    type Item = (Path, Polarity, &'a Proposition);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, polarity, proposition) = self.stack.pop()?;
+        for (index, prop) in children(proposition).into_iter().enumerate().rev() {
+            self.stack.push((path.child(index), polarity.of_child(proposition, index), prop));
+        }
+        Some((path, polarity, proposition))
+    }
+}
+
+/// Gather occurrences of things by combining the polarities of those which are equal.
+pub(crate) fn combine_all<T: Eq + Hash>(occurrences: impl Iterator<Item = (T, Polarity)>) -> HashMap<T, Polarity> {
+    let mut polarities: HashMap<T, Polarity> = HashMap::new();
+    for (item, polarity) in occurrences {
+        polarities.entry(item).and_modify(|p| *p = p.combine(polarity)).or_insert(polarity);
+    }
+    polarities
+}
+
+/// Return the content of proposition, which for atoms is nothing rather than themselves.
+fn children(proposition: &Proposition) -> Vec<&Proposition> {
+    match proposition {
+        Proposition::Atom(_) => Vec::new(),
+        _ => proposition.content(),
+    }
+}
+
+impl Proposition {
+    /// Return every subformula of self, self included, outermost first and from left to
+    /// right, along with its path and its polarity in self.
+    pub fn subformulas(&self) -> Subformulas<'_> {
+        self.subformulas_with(Polarity::Positive)
+    }
+
+    /// Return every subformula of self as `subformulas` does, with self itself taken to
+    /// have polarity.
+    pub fn subformulas_with(&self, polarity: Polarity) -> Subformulas<'_> {
+        Subformulas { stack: vec![(Path::root(), polarity, self)] }
+    }
+
+    /// Return each distinct subformula of self, self included, with the combined polarity
+    /// of all its occurrences in self.
+    pub fn polarities(&self) -> HashMap<&Proposition, Polarity> {
+        combine_all(self.subformulas().map(|(_, polarity, prop)| (prop, polarity)))
+    }
+
+    /// Return the subformula of self at path, if there is one.
+    pub fn get(&self, path: &Path) -> Option<&Proposition> {
+        path.indices().iter().try_fold(self, |prop, &index| children(prop).get(index).copied())
+    }
+
+    /// Return the subformula of self at path mutably, if there is one.
+    pub fn get_mut(&mut self, path: &Path) -> Option<&mut Proposition> {
+        path.indices().iter().try_fold(self, |prop, &index| {
+            let mut content = prop.content_mut();
+            (index < content.len()).then(|| content.swap_remove(index))
+        })
+    }
+
+    /// Put new at path in self, returning the subformula it replaces, or return None and
+    /// leave self unchanged if there is no subformula at path.
+    pub fn replace_at(&mut self, path: &Path, new: Proposition) -> Option<Proposition> {
+        self.get_mut(path).map(|prop| std::mem::replace(prop, new))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::path::{Path, Polarity};
+    use crate::proposition::Proposition;
+    use crate::sequent::{Coordinates, Sequent, Side};
+
+    #[test]
+    fn test_paths() {
+        let path = Path::from(vec![1, 0]);
+        assert_eq!(path.to_string(), "1.0");
+        assert_eq!(Path::root().to_string(), "ε");
+        assert_eq!(path.parent(), Some(Path::root().child(1)));
+        assert_eq!(Path::root().parent(), None);
+        assert!(Path::root().is_prefix_of(&path));
+        assert!(path.is_prefix_of(&path));
+        assert!(!path.is_prefix_of(&Path::from(vec![1])));
+    }
+
+    #[test]
+    fn test_subformulas() {
+        let proposition: Proposition = "~(A > B) & (C <> ∀<x> P(<x>))".parse().unwrap();
+        let subformulas: Vec<(String, Polarity, String)> = proposition
+            .subformulas()
+            .map(|(path, polarity, prop)| (path.to_string(), polarity, prop.to_string()))
+            .collect();
+        let expected = [
+            ("ε", Polarity::Positive, proposition.to_string()),
+            ("0", Polarity::Positive, "~((A > B))".to_string()),
+            ("0.0", Polarity::Negative, "(A > B)".to_string()),
+            ("0.0.0", Polarity::Positive, "A".to_string()),
+            ("0.0.1", Polarity::Negative, "B".to_string()),
+            ("1", Polarity::Positive, "(C <> ∀<x>(P(<x>)))".to_string()),
+            ("1.0", Polarity::Both, "C".to_string()),
+            ("1.1", Polarity::Both, "∀<x>(P(<x>))".to_string()),
+            ("1.1.0", Polarity::Both, "P(<x>)".to_string()),
+        ];
+        let expected: Vec<(String, Polarity, String)> = expected
+            .into_iter()
+            .map(|(path, polarity, prop)| (path.to_string(), polarity, prop))
+            .collect();
+        assert_eq!(subformulas, expected);
+
+        for (path, _, prop) in proposition.subformulas() {
+            assert_eq!(proposition.get(&path), Some(prop));
+        }
+    }
+
+    #[test]
+    fn test_polarities() {
+        let proposition: Proposition = "(A > B) & (B > A) & ~~C & (D <> D)".parse().unwrap();
+        let polarities = proposition.polarities();
+        let polarity = |s: &str| polarities[&s.parse::<Proposition>().unwrap()];
+        assert_eq!(polarity("A"), Polarity::Both);
+        assert_eq!(polarity("C"), Polarity::Positive);
+        assert_eq!(polarity("~C"), Polarity::Negative);
+        assert_eq!(polarity("D"), Polarity::Both);
+        assert_eq!(polarity("B > A"), Polarity::Positive);
+        assert_eq!(Polarity::Negative.combine(Polarity::Negative), Polarity::Negative);
+    }
+
+    #[test]
+    fn test_get_and_replace_at() {
+        let mut proposition: Proposition = "A > ~B".parse().unwrap();
+        assert_eq!(proposition.get(&Path::from(vec![1, 0])), Some(&"B".parse().unwrap()));
+        assert_eq!(proposition.get(&Path::from(vec![2])), None);
+        // atoms have no subformulas, even though their content is themselves
+        assert_eq!(proposition.get(&Path::from(vec![0, 0])), None);
+
+        let replaced = proposition.replace_at(&Path::from(vec![1, 0]), "C & D".parse().unwrap());
+        assert_eq!(replaced, Some("B".parse().unwrap()));
+        assert_eq!(proposition.to_string(), "(A > ~((C & D)))");
+        assert_eq!(proposition.replace_at(&Path::from(vec![0, 3]), Proposition::Verum), None);
+        assert_eq!(proposition.replace_at(&Path::root(), Proposition::Verum), Some("A > ~(C & D)".parse().unwrap()));
+        assert_eq!(proposition, Proposition::Verum);
+    }
+
+    #[test]
+    fn test_sequent_subformulas_and_replace_at() {
+        let mut sequent: Sequent = "A > B |~ ~C".parse().unwrap();
+        let polarities: Vec<(Side, String, Polarity)> = sequent
+            .subformulas()
+            .map(|(coordinates, _, polarity, prop)| (coordinates.side, prop.to_string(), polarity))
+            .collect();
+        assert_eq!(polarities, vec![
+            (Side::Antecedent, "(A > B)".to_string(), Polarity::Negative),
+            (Side::Antecedent, "A".to_string(), Polarity::Positive),
+            (Side::Antecedent, "B".to_string(), Polarity::Negative),
+            (Side::Consequent, "~(C)".to_string(), Polarity::Positive),
+            (Side::Consequent, "C".to_string(), Polarity::Negative),
+        ]);
+
+        let consequent = Coordinates { side: Side::Consequent, index: 0 };
+        assert_eq!(sequent.get(&consequent, &Path::from(vec![0])), Some(&"C".parse().unwrap()));
+        assert_eq!(sequent.replace_at(&consequent, &Path::from(vec![0]), "D v E".parse().unwrap()), Some("C".parse().unwrap()));
+        assert_eq!(sequent.to_string(), "(A > B) |~ ~((D v E))");
+        let missing = Coordinates { side: Side::Antecedent, index: 1 };
+        assert_eq!(sequent.replace_at(&missing, &Path::root(), Proposition::Verum), None);
+    }
+}
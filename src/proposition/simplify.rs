@@ -0,0 +1,282 @@
+use std::fmt::{Display, Formatter};
+use crate::proposition::path::Path;
+use crate::proposition::Proposition;
+
+/// Rebuilds a conjunction or disjunction from its content.
+type Join = fn(Vec<Proposition>) -> Proposition;
+
+/// Rewrites a proposition, if the rule applies to it.
+type Rule = fn(&Proposition) -> Option<Proposition>;
+
+/// The rewrite rules `Proposition::simplify` applies.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Rewrite {
+    /// Evaluate connectives applied to `⊤` or `⊥` as far as possible, eg. `A & ⊤` to `A`
+    /// and `A v ⊤` to `⊤`.
+    ConstantFolding,
+    /// `~~A` to `A`.
+    DoubleNegation,
+    /// Merge a conjunction directly inside a conjunction, or a disjunction directly inside
+    /// a disjunction, and replace a conjunction or disjunction of one proposition with it.
+    Association,
+    /// Remove repeated conjuncts or disjuncts, eg. `A & B & A` to `A & B`.
+    Idempotence,
+    /// Remove conjuncts or disjuncts that others make redundant, eg. `A & (A v B)` to `A`
+    /// and `A v (A & B)` to `A`.
+    Absorption,
+    /// Remove a quantifier whose variable doesn't occur free in its content, eg. `∀<x> A`
+    /// to `A`. Like `prenex`, this assumes the domain is not empty: over an empty domain
+    /// `∀<x> ⊥` is true and `∃<x> ⊤` false.
+    VacuousQuantifier,
+}
+
+impl Display for Rewrite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Rewrite::ConstantFolding => "constant folding",
+            Rewrite::DoubleNegation => "double negation",
+            Rewrite::Association => "association",
+            Rewrite::Idempotence => "idempotence",
+            Rewrite::Absorption => "absorption",
+            Rewrite::VacuousQuantifier => "vacuous quantifier",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One rewrite made by `Proposition::simplify`: the rule applied, where it was applied in
+/// the proposition as it stood at the time, and the subformula before and after.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    pub rule: Rewrite,
+    pub path: Path,
+    pub before: Proposition,
+    pub after: Proposition,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}: {} becomes {}", self.rule, self.path, self.before, self.after)
+    }
+}
+
+impl Proposition {
+    /// Return a simplified proposition equivalent to self over any non-empty domain, along
+    /// with each rewrite made to get there, in order. Rules are applied to the outermost
+    /// subformula they can be, one at a time, until none applies anywhere.
+    pub fn simplify(&self) -> (Proposition, Vec<Step>) {
+        let mut simplified = self.clone();
+        let mut steps: Vec<Step> = Vec::new();
+        while let Some((path, rule, after)) = simplified
+            .subformulas()
+            .find_map(|(path, _, prop)| rewrite(prop).map(|(rule, after)| (path, rule, after)))
+        {
+            let before = simplified.replace_at(&path, after.clone()).expect("path was found in simplified");
+            steps.push(Step { rule, path, before, after });
+        }
+        (simplified, steps)
+    }
+}
+
+/// Return the first rule which applies to proposition itself, and what it rewrites it to.
+fn rewrite(proposition: &Proposition) -> Option<(Rewrite, Proposition)> {
+    let rules: [(Rewrite, Rule); 6] = [
+        (Rewrite::ConstantFolding, fold_constants),
+        (Rewrite::DoubleNegation, remove_double_negation),
+        (Rewrite::Association, associate),
+        (Rewrite::Idempotence, remove_repeats),
+        (Rewrite::Absorption, absorb),
+        (Rewrite::VacuousQuantifier, remove_vacuous_quantifier),
+    ];
+    rules.into_iter().find_map(|(rule, apply)| apply(proposition).map(|after| (rule, after)))
+}
+
+fn fold_constants(proposition: &Proposition) -> Option<Proposition> {
+    use Proposition::{Falsum, Verum};
+    let negation = |prop: &Proposition| Proposition::Negation(Box::new(prop.clone()));
+    match proposition {
+        Proposition::Negation(negatum) => match **negatum {
+            Verum => Some(Falsum),
+            Falsum => Some(Verum),
+            _ => None,
+        },
+        Proposition::Conditional(left, right) => match (&**left, &**right) {
+            (Falsum, _) | (_, Verum) => Some(Verum),
+            (Verum, right) => Some(right.clone()),
+            (left, Falsum) => Some(negation(left)),
+            _ => None,
+        },
+        Proposition::Biconditional(left, right) => match (&**left, &**right) {
+            (Verum, other) | (other, Verum) => Some(other.clone()),
+            (Falsum, other) | (other, Falsum) => Some(negation(other)),
+            _ => None,
+        },
+        Proposition::Conjunction(conjuncts) => fold_join(conjuncts, Verum, Falsum, Proposition::Conjunction),
+        Proposition::Disjunction(disjuncts) => fold_join(disjuncts, Falsum, Verum, Proposition::Disjunction),
+        _ => None,
+    }
+}
+
+/// Fold the constants in the content of a conjunction or disjunction, where identity is the
+/// constant which makes no difference to it and zero the one which decides it.
+fn fold_join(
+    content: &[Proposition],
+    identity: Proposition,
+    zero: Proposition,
+    join: Join,
+) -> Option<Proposition> {
+    if content.contains(&zero) { return Some(zero) }
+    if !content.contains(&identity) { return None }
+    let rest: Vec<Proposition> = content.iter().filter(|prop| **prop != identity).cloned().collect();
+    Some(match rest.len() {
+        0 => identity,
+        _ => rejoin(rest, join),
+    })
+}
+
+/// If proposition is a conjunction or disjunction, return its content and how to rebuild it.
+fn join_content(proposition: &Proposition) -> Option<(&[Proposition], Join)> {
+    match proposition {
+        Proposition::Conjunction(conjuncts) => Some((conjuncts, Proposition::Conjunction)),
+        Proposition::Disjunction(disjuncts) => Some((disjuncts, Proposition::Disjunction)),
+        _ => None,
+    }
+}
+
+/// Join content with join, unless there is only one proposition to join.
+fn rejoin(mut content: Vec<Proposition>, join: Join) -> Proposition {
+    match content.len() {
+        1 => content.remove(0),
+        _ => join(content),
+    }
+}
+
+fn remove_double_negation(proposition: &Proposition) -> Option<Proposition> {
+    match proposition {
+        Proposition::Negation(negatum) => match &**negatum {
+            Proposition::Negation(content) => Some((**content).clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn associate(proposition: &Proposition) -> Option<Proposition> {
+    let (content, join) = join_content(proposition)?;
+    if let [only] = content { return Some(only.clone()) }
+    let nested = |prop: &Proposition| std::mem::discriminant(prop) == std::mem::discriminant(proposition);
+    if !content.iter().any(nested) { return None }
+    let merged = content
+        .iter()
+        .flat_map(|prop| if nested(prop) { prop.content() } else { vec![prop] })
+        .cloned()
+        .collect();
+    Some(join(merged))
+}
+
+fn remove_repeats(proposition: &Proposition) -> Option<Proposition> {
+    let (content, join) = join_content(proposition)?;
+    let mut distinct: Vec<Proposition> = Vec::new();
+    for prop in content {
+        if !distinct.iter().any(|seen| seen.alpha_equivalent(prop)) { distinct.push(prop.clone()) }
+    }
+    if distinct.len() == content.len() { return None }
+    Some(rejoin(distinct, join))
+}
+
+fn absorb(proposition: &Proposition) -> Option<Proposition> {
+    let (content, join) = join_content(proposition)?;
+    // a disjunct of a conjunction is absorbed if one of its disjuncts is another conjunct,
+    // and dually for disjunctions
+    let absorbed = |index: usize| match (proposition, &content[index]) {
+        (Proposition::Conjunction(_), Proposition::Disjunction(inner))
+        | (Proposition::Disjunction(_), Proposition::Conjunction(inner)) => content
+            .iter()
+            .enumerate()
+            .any(|(other, prop)| other != index && inner.iter().any(|inner| inner.alpha_equivalent(prop))),
+        _ => false,
+    };
+    let index = (0..content.len()).find(|index| absorbed(*index))?;
+    let mut rest = content.to_vec();
+    rest.remove(index);
+    Some(rejoin(rest, join))
+}
+
+/// Remove a quantifier which binds nothing. This is only sound over non-empty domains, so
+/// it can change whether a proposition holds in a `Structure` whose domain is empty.
+fn remove_vacuous_quantifier(proposition: &Proposition) -> Option<Proposition> {
+    match proposition {
+        Proposition::Existential(var, content) | Proposition::Universal(var, content) => {
+            (!content.free_variables().contains(var)).then(|| (**content).clone())
+        },
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::path::Path;
+    use crate::proposition::simplify::Rewrite;
+    use crate::proposition::{Atom, Proposition, Valuation};
+
+    fn simplified(s: &str) -> String {
+        s.parse::<Proposition>().unwrap().simplify().0.to_string()
+    }
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(simplified("~~A"), "A");
+        assert_eq!(simplified("~~~~(A > B)"), "(A > B)");
+        assert_eq!(simplified("A & B & A"), "(A & B)");
+        assert_eq!(simplified("A & (B & A)"), "(A & B)");
+        assert_eq!(simplified("A & (A v B)"), "A");
+        assert_eq!(simplified("(A & B) v C v A"), "(C v A)");
+        assert_eq!(simplified("∀<x> ∃<y> P(<x>)"), "∀<x>(P(<x>))");
+        assert_eq!(simplified("∀<x> P(<x>) & ∀<y> P(<y>)"), "∀<x>(P(<x>))");
+    }
+
+    #[test]
+    fn test_simplify_constants() {
+        assert_eq!(simplified("A & ⊤"), "A");
+        assert_eq!(simplified("A & ⊤ & B"), "(A & B)");
+        assert_eq!(simplified("A v ~⊥"), "⊤");
+        assert_eq!(simplified("(A > ⊥) <> ⊥"), "A");
+        assert_eq!(simplified("⊥ > A"), "⊤");
+        assert_eq!(simplified("∃<x> ⊥ v B"), "B");
+    }
+
+    #[test]
+    fn test_simplify_trace() {
+        let proposition: Proposition = "C > ~~(A & ⊤)".parse().unwrap();
+        let (simplified, steps) = proposition.simplify();
+        assert_eq!(simplified.to_string(), "(C > A)");
+        let trace: Vec<(Rewrite, Path)> = steps.iter().map(|step| (step.rule, step.path.clone())).collect();
+        assert_eq!(trace, vec![
+            (Rewrite::DoubleNegation, Path::from(vec![1])),
+            (Rewrite::ConstantFolding, Path::from(vec![1])),
+        ]);
+        assert_eq!(steps[0].to_string(), "double negation at 1: ~(~((A & ⊤))) becomes (A & ⊤)");
+
+        let (unchanged, steps) = simplified.simplify();
+        assert_eq!(unchanged, simplified);
+        assert!(steps.is_empty());
+    }
+
+    #[test]
+    fn test_simplify_preserves_truth() {
+        let sources = [
+            "~~(A & (A v B)) > C & ⊤",
+            "(A v ~⊤) <> (B & B & (A v (A & C)))",
+            "~(A & ~~B) v (C > ⊥) v (A & (B & A))",
+        ];
+        for source in sources {
+            let proposition: Proposition = source.parse().unwrap();
+            let (simplified, _) = proposition.simplify();
+            let atoms: Vec<Atom> = proposition.atoms().into_iter().cloned().collect();
+            for valuation in Valuation::all(&atoms) {
+                assert_eq!(proposition.evaluate(&valuation), simplified.evaluate(&valuation), "{source}");
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use crate::proposition::atom::{Atom, Predicate};
+use crate::proposition::term::Term;
+use crate::proposition::Proposition;
+
+/// A finite first-order model: a domain of elements, what each name and function symbol
+/// denotes, and the extension of each predicate, ie. the tuples of elements it is true of.
+/// A name which is itself an element of the domain denotes that element unless it has been
+/// interpreted otherwise.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Structure {
+    domain: Vec<String>,
+    names: HashMap<String, String>,
+    functions: HashMap<String, HashMap<Vec<String>, String>>,
+    extensions: HashMap<Predicate, HashSet<Vec<String>>>,
+}
+
+/// The elements variables are bound to while evaluating a proposition.
+type Assignment = HashMap<String, String>;
+
+impl Structure {
+    /// Return a structure over domain which interprets nothing yet.
+    pub fn new(domain: Vec<String>) -> Structure {
+        Structure { domain: domain.into_iter().unique().collect(), ..Structure::default() }
+    }
+
+    pub fn domain(&self) -> &[String] {
+        &self.domain
+    }
+
+    /// Make name denote element.
+    ///
+    /// # Panics
+    /// Panics if element is not in self's domain.
+    pub fn interpret_name(&mut self, name: &str, element: &str) {
+        self.check(element);
+        self.names.insert(name.to_string(), element.to_string());
+    }
+
+    /// Make the function symbol applied to args denote value.
+    ///
+    /// # Panics
+    /// Panics if value or any of args is not in self's domain.
+    pub fn interpret_function(&mut self, symbol: &str, args: Vec<String>, value: &str) {
+        args.iter().for_each(|arg| self.check(arg));
+        self.check(value);
+        self.functions.entry(symbol.to_string()).or_default().insert(args, value.to_string());
+    }
+
+    /// Make predicate true of tuple. Predicates without arguments, like the prose atom `A`,
+    /// are made true by adding the empty tuple.
+    ///
+    /// # Panics
+    /// Panics if any of tuple is not in self's domain.
+    pub fn add_to_extension(&mut self, predicate: Predicate, tuple: Vec<String>) {
+        tuple.iter().for_each(|element| self.check(element));
+        self.extensions.entry(predicate).or_default().insert(tuple);
+    }
+
+    fn check(&self, element: &str) {
+        if !self.domain.iter().any(|e| e == element) {
+            panic!("{} is not in the domain of this structure", element)
+        }
+    }
+
+    /// Return the element term denotes when variables are bound as in assignment.
+    fn denotation(&self, term: &Term, assignment: &Assignment) -> Result<String, ModelError> {
+        match term {
+            Term::Variable(var) => assignment.get(var).cloned().ok_or_else(|| ModelError::FreeVariable(var.clone())),
+            Term::Name(name) => match self.names.get(name) {
+                Some(element) => Ok(element.clone()),
+                None if self.domain.contains(name) => Ok(name.clone()),
+                None => Err(ModelError::UninterpretedName(name.clone())),
+            },
+            Term::Function(symbol, args) => {
+                let args: Vec<String> = args.iter().map(|arg| self.denotation(arg, assignment)).try_collect()?;
+                self.functions
+                    .get(symbol)
+                    .and_then(|values| values.get(&args))
+                    .cloned()
+                    .ok_or_else(|| ModelError::UninterpretedFunction(symbol.clone(), args))
+            },
+        }
+    }
+
+    fn holds(&self, atom: &Atom, assignment: &Assignment) -> Result<bool, ModelError> {
+        let args: Vec<String> = atom.args.iter().map(|arg| self.denotation(arg, assignment)).try_collect()?;
+        match atom.predicate {
+            Predicate::Identity => Ok(args.iter().all_equal()),
+            _ => Ok(self.extensions.get(&atom.predicate).is_some_and(|extension| extension.contains(&args))),
+        }
+    }
+
+    fn evaluate(&self, proposition: &Proposition, assignment: &Assignment) -> Result<bool, ModelError> {
+        match proposition {
+            Proposition::Atom(atom) => self.holds(atom, assignment),
+            Proposition::Verum => Ok(true),
+            Proposition::Falsum => Ok(false),
+            Proposition::Negation(negatum) => Ok(!self.evaluate(negatum, assignment)?),
+            Proposition::Conditional(left, right) => {
+                Ok(!self.evaluate(left, assignment)? || self.evaluate(right, assignment)?)
+            },
+            Proposition::Biconditional(left, right) => {
+                Ok(self.evaluate(left, assignment)? == self.evaluate(right, assignment)?)
+            },
+            Proposition::Conjunction(conjuncts) => {
+                for conjunct in conjuncts {
+                    if !self.evaluate(conjunct, assignment)? { return Ok(false) }
+                }
+                Ok(true)
+            },
+            Proposition::Disjunction(disjuncts) => {
+                for disjunct in disjuncts {
+                    if self.evaluate(disjunct, assignment)? { return Ok(true) }
+                }
+                Ok(false)
+            },
+            Proposition::Existential(var, content) => {
+                for element in &self.domain {
+                    if self.evaluate(content, &bind(assignment, var, element))? { return Ok(true) }
+                }
+                Ok(false)
+            },
+            Proposition::Universal(var, content) => {
+                for element in &self.domain {
+                    if !self.evaluate(content, &bind(assignment, var, element))? { return Ok(false) }
+                }
+                Ok(true)
+            },
+        }
+    }
+}
+
+/// Return a copy of assignment with var bound to element, in place of any earlier binding.
+fn bind(assignment: &Assignment, var: &str, element: &str) -> Assignment {
+    let mut bound = assignment.clone();
+    bound.insert(var.to_string(), element.to_string());
+    bound
+}
+
+impl Proposition {
+    /// Whether self is true in structure. Quantifiers range over structure's domain, so
+    /// self must be closed.
+    pub fn is_satisfied_by(&self, structure: &Structure) -> Result<bool, ModelError> {
+        structure.evaluate(self, &Assignment::new())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ModelError {
+    /// The proposition has a free occurrence of this variable.
+    FreeVariable(String),
+    /// The structure doesn't say what this name denotes.
+    UninterpretedName(String),
+    /// The structure doesn't say what this function symbol gives for these arguments.
+    UninterpretedFunction(String, Vec<String>),
+}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::FreeVariable(var) => write!(f, "<{}> is free, so has no denotation", var),
+            ModelError::UninterpretedName(name) => write!(f, "no denotation for <{}>", name),
+            ModelError::UninterpretedFunction(symbol, args) => {
+                write!(f, "no denotation for {}({})", symbol, args.iter().map(|arg| format!("<{}>", arg)).join(", "))
+            },
+        }
+    }
+}
+
+impl Error for ModelError {}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::structure::{ModelError, Structure};
+    use crate::proposition::{Atom, Predicate, Proposition};
+    use crate::sequent::Sequent;
+
+    fn symbol(s: &str) -> Predicate {
+        Predicate::Symbol(s.to_string())
+    }
+
+    /// Two cats, one of whom is the other's mother, and a mat the mother is on.
+    fn cats() -> Structure {
+        let mut structure = Structure::new(vec!["kitty".to_string(), "tom".to_string(), "mat".to_string()]);
+        structure.interpret_name("the mat", "mat");
+        for cat in ["kitty", "tom"] {
+            structure.add_to_extension(symbol("Cat"), vec![cat.to_string()]);
+            structure.interpret_function("mother", vec![cat.to_string()], "tom");
+        }
+        structure.interpret_function("mother", vec!["mat".to_string()], "mat");
+        structure.add_to_extension(symbol("On"), vec!["tom".to_string(), "mat".to_string()]);
+        structure.add_to_extension(Atom::prose("A").predicate, Vec::new());
+        structure
+    }
+
+    fn satisfied(s: &str) -> Result<bool, ModelError> {
+        s.parse::<Proposition>().unwrap().is_satisfied_by(&cats())
+    }
+
+    #[test]
+    fn test_is_satisfied_by() {
+        assert_eq!(satisfied("Cat(<kitty>) & ~Cat(<the mat>)"), Ok(true));
+        assert_eq!(satisfied("A & ~B"), Ok(true));
+        assert_eq!(satisfied("On(mother(<kitty>), <the mat>)"), Ok(true));
+        assert_eq!(satisfied("mother(<kitty>) = <tom> & mother(<tom>) = <tom>"), Ok(true));
+        assert_eq!(satisfied("<kitty> = <tom>"), Ok(false));
+    }
+
+    #[test]
+    fn test_quantifiers_range_over_domain() {
+        assert_eq!(satisfied("∀<x>(Cat(<x>) > Cat(mother(<x>)))"), Ok(true));
+        assert_eq!(satisfied("∃<x> On(<x>, <the mat>)"), Ok(true));
+        assert_eq!(satisfied("∀<x> Cat(<x>)"), Ok(false));
+        assert_eq!(satisfied("∀<x> ∃<y> mother(<x>) = <y>"), Ok(true));
+        assert_eq!(satisfied("∃<x> ∀<y> (Cat(<y>) > mother(<y>) = <x>)"), Ok(true));
+        // the inner quantifier rebinds x
+        assert_eq!(satisfied("∃<x>(Cat(<x>) & ∃<x> ~Cat(<x>))"), Ok(true));
+        assert!(Proposition::Existential("x".to_string(), Box::new(Proposition::Verum))
+            .is_satisfied_by(&Structure::new(Vec::new()))
+            .is_ok_and(|satisfied| !satisfied));
+    }
+
+    #[test]
+    fn test_sequent_is_satisfied_by() {
+        let satisfied = |s: &str| s.parse::<Sequent>().unwrap().is_satisfied_by(&cats());
+        assert_eq!(satisfied("∀<x> Cat(<x>) |~ B"), Ok(true));
+        assert_eq!(satisfied("Cat(<kitty>), A |~ B, On(<tom>, <the mat>)"), Ok(true));
+        assert_eq!(satisfied("∃<x> Cat(<x>) |- ∀<x> Cat(<x>)"), Ok(false));
+        assert_eq!(satisfied("|~"), Ok(false));
+    }
+
+    #[test]
+    fn test_model_errors() {
+        assert_eq!(satisfied("Cat(<x>)"), Err(ModelError::FreeVariable("x".to_string())));
+        assert_eq!(satisfied("Cat(<felix>)"), Err(ModelError::UninterpretedName("felix".to_string())));
+        assert_eq!(
+            satisfied("∃<x> father(<x>) = <tom>"),
+            Err(ModelError::UninterpretedFunction("father".to_string(), vec!["kitty".to_string()]))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interpretations_must_be_in_domain() {
+        cats().interpret_name("felix", "felix");
+    }
+}
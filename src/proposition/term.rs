@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use crate::proposition::create::function_from_string;
+use crate::proposition::lex::{quote, unquote};
+
+/// The things atoms are about. Simple terms are written between angle brackets: single
+/// lowercase letters are variables (`<x>`), and anything else is a name (`<kitty>`,
+/// `<the mat>`). Function applications are written `mother(<kitty>)`, or within angle
+/// brackets (`<mother(<kitty>)>`) in prose atoms. Names which would otherwise be read as
+/// something else are quoted, eg. `<"x">` is the name x rather than a variable.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+pub enum Term {
+    Variable(String),
+    Name(String),
+    /// A function symbol applied to its arguments, eg. `mother(father(<kitty>))`.
+    Function(String, Vec<Term>),
+}
+
+impl Term {
+    /// Classify the contents of a `<...>` term as a function application, variable or name.
+    pub fn from_contents(contents: &str) -> Term {
+        if let Some((name, end)) = unquote(contents, 0) {
+            if end == contents.len() { return Term::Name(name) }
+        }
+        if let Some(function) = function_from_string(contents) { return function }
+        let mut chars = contents.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_lowercase() => Term::Variable(contents.to_string()),
+            _ => Term::Name(contents.to_string()),
+        }
+    }
+
+    /// Return the names in self, including those nested in function applications.
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            Term::Variable(_) => Vec::new(),
+            Term::Name(name) => vec![name.clone()],
+            Term::Function(_, args) => args.iter().flat_map(|arg| arg.names()).collect(),
+        }
+    }
+
+    /// Return the variables in self, including those nested in function applications.
+    pub fn variables(&self) -> Vec<String> {
+        match self {
+            Term::Variable(var) => vec![var.clone()],
+            Term::Name(_) => Vec::new(),
+            Term::Function(_, args) => args.iter().flat_map(|arg| arg.variables()).collect(),
+        }
+    }
+
+    /// Return the function symbols in self along with the number of arguments each takes.
+    pub fn functions(&self) -> Vec<(String, usize)> {
+        match self {
+            Term::Function(symbol, args) => {
+                let mut functions = vec![(symbol.clone(), args.len())];
+                for arg in args { functions.append(&mut arg.functions()); }
+                functions
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Return self and every term nested within it.
+    pub fn subterms(&self) -> Vec<&Term> {
+        let mut subterms = vec![self];
+        if let Term::Function(_, args) = self {
+            for arg in args { subterms.append(&mut arg.subterms()); }
+        }
+        subterms
+    }
+
+    /// Whether self contains no variables.
+    pub fn is_ground(&self) -> bool {
+        self.variables().is_empty()
+    }
+
+    /// Return each term made by replacing exactly one occurrence of from in self with to.
+    pub fn replacements(&self, from: &Term, to: &Term) -> Vec<Term> {
+        if self == from { return vec![to.clone()] }
+        let mut replaced: Vec<Term> = Vec::new();
+        if let Term::Function(symbol, args) = self {
+            for (index, arg) in args.iter().enumerate() {
+                for replacement in arg.replacements(from, to) {
+                    let mut args = args.clone();
+                    args[index] = replacement;
+                    replaced.push(Term::Function(symbol.clone(), args));
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Replace each occurrence of the variable var in self with term.
+    pub fn substitute(&mut self, var: &str, term: &Term) {
+        match self {
+            Term::Variable(v) if v == var => *self = term.clone(),
+            Term::Function(_, args) => {
+                for arg in args.iter_mut() { arg.substitute(var, term); }
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Display for Term {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Term::Variable(var) => write!(f, "<{}>", var),
+            Term::Name(name) => match Term::from_contents(name) == *self {
+                true => write!(f, "<{}>", name),
+                false => write!(f, "<{}>", quote(name)),
+            },
+            Term::Function(symbol, args) => write!(f, "{}({})", symbol, args.iter().join(", ")),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::term::Term;
+
+    fn kitty() -> Term {
+        Term::Name("kitty".to_string())
+    }
+
+    #[test]
+    fn test_from_contents() {
+        assert_eq!(Term::from_contents("x"), Term::Variable("x".to_string()));
+        assert_eq!(Term::from_contents("kitty"), kitty());
+        assert_eq!(Term::from_contents("the mat"), Term::Name("the mat".to_string()));
+        assert_eq!(Term::from_contents("X"), Term::Name("X".to_string()));
+        assert_eq!(Term::from_contents("mother(<kitty>)"), Term::Function("mother".to_string(), vec![kitty()]));
+        assert_eq!(Term::from_contents("\"x\""), Term::Name("x".to_string()));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let terms = [
+            kitty(),
+            Term::Variable("x".to_string()),
+            Term::Name("x".to_string()),
+            Term::Name("f(<a>)".to_string()),
+            Term::Name("\"quoted\"".to_string()),
+        ];
+        for term in terms {
+            let written = term.to_string();
+            assert_eq!(Term::from_contents(&written[1..written.len() - 1]), term, "{written}");
+        }
+        assert_eq!(Term::Name("x".to_string()).to_string(), "<\"x\">");
+        assert_eq!(kitty().to_string(), "<kitty>");
+    }
+
+    #[test]
+    fn test_substitute_nested() {
+        let mut term = Term::Function(
+            "mother".to_string(),
+            vec![Term::Function("father".to_string(), vec![Term::Variable("x".to_string())])]
+        );
+        term.substitute("x", &kitty());
+        assert_eq!(term.to_string(), "mother(father(<kitty>))");
+        assert!(term.is_ground());
+        assert_eq!(term.names(), vec!["kitty".to_string()]);
+        assert_eq!(term.functions(), vec![("mother".to_string(), 1), ("father".to_string(), 1)]);
+        assert_eq!(term.subterms().len(), 3);
+    }
+}
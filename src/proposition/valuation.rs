@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use crate::proposition::atom::Atom;
+use crate::proposition::Proposition;
+
+/// An assignment of truth values to atoms.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Valuation {
+    values: HashMap<Atom, bool>,
+}
+
+impl Valuation {
+    pub fn new() -> Valuation {
+        Valuation::default()
+    }
+
+    /// Assign value to atom, replacing any value it already had.
+    pub fn set(&mut self, atom: Atom, value: bool) {
+        self.values.insert(atom, value);
+    }
+
+    /// Return the value of atom, if it has one.
+    pub fn get(&self, atom: &Atom) -> Option<bool> {
+        self.values.get(atom).copied()
+    }
+
+    /// Return every valuation of exactly the atoms given, of which there are 2^n for n
+    /// distinct atoms.
+    pub fn all(atoms: &[Atom]) -> impl Iterator<Item = Valuation> {
+        let atoms: Vec<Atom> = atoms.iter().unique().cloned().collect();
+        let count = atoms.len();
+        itertools::repeat_n([false, true], count)
+            .multi_cartesian_product()
+            // with no atoms there is still the one, empty, valuation
+            .chain((count == 0).then(Vec::new))
+            .map(move |values| atoms.iter().cloned().zip(values).collect())
+    }
+}
+
+impl FromIterator<(Atom, bool)> for Valuation {
+    fn from_iter<I: IntoIterator<Item = (Atom, bool)>>(iter: I) -> Self {
+        Valuation { values: iter.into_iter().collect() }
+    }
+}
+
+impl Extend<(Atom, bool)> for Valuation {
+    fn extend<I: IntoIterator<Item = (Atom, bool)>>(&mut self, iter: I) {
+        self.values.extend(iter);
+    }
+}
+
+impl Proposition {
+    /// Return the truth value of self under valuation. Only the quantifier-free fragment
+    /// can be evaluated; see `is_satisfied_by` for quantified propositions.
+    pub fn evaluate(&self, valuation: &Valuation) -> Result<bool, EvaluationError> {
+        match self {
+            Proposition::Atom(atom) => valuation.get(atom).ok_or_else(|| EvaluationError::Unassigned(atom.clone())),
+            Proposition::Verum => Ok(true),
+            Proposition::Falsum => Ok(false),
+            Proposition::Negation(negatum) => Ok(!negatum.evaluate(valuation)?),
+            Proposition::Conditional(left, right) => Ok(!left.evaluate(valuation)? || right.evaluate(valuation)?),
+            Proposition::Biconditional(left, right) => Ok(left.evaluate(valuation)? == right.evaluate(valuation)?),
+            Proposition::Conjunction(conjuncts) => {
+                for conjunct in conjuncts {
+                    if !conjunct.evaluate(valuation)? { return Ok(false) }
+                }
+                Ok(true)
+            },
+            Proposition::Disjunction(disjuncts) => {
+                for disjunct in disjuncts {
+                    if disjunct.evaluate(valuation)? { return Ok(true) }
+                }
+                Ok(false)
+            },
+            Proposition::Existential(_, _) | Proposition::Universal(_, _) => Err(EvaluationError::Quantified),
+        }
+    }
+
+    /// Return every valuation of the atoms in self, one at a time.
+    pub fn valuations(&self) -> impl Iterator<Item = Valuation> {
+        let atoms: Vec<Atom> = self.atoms().into_iter().cloned().collect();
+        Valuation::all(&atoms)
+    }
+
+    /// Whether self is true under every valuation of its atoms. Stops at the first
+    /// valuation which makes self false.
+    pub fn is_tautology(&self) -> Result<bool, EvaluationError> {
+        let values = self.valuations().map(|valuation| self.evaluate(&valuation));
+        itertools::process_results(values, |mut values| values.all(|value| value))
+    }
+
+    /// Whether self is true under some valuation of its atoms. Stops at the first
+    /// valuation which makes self true.
+    pub fn is_satisfiable(&self) -> Result<bool, EvaluationError> {
+        let values = self.valuations().map(|valuation| self.evaluate(&valuation));
+        itertools::process_results(values, |mut values| values.any(|value| value))
+    }
+
+    /// Whether self is false under every valuation of its atoms.
+    pub fn is_contradiction(&self) -> Result<bool, EvaluationError> {
+        Ok(!self.is_satisfiable()?)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EvaluationError {
+    /// The valuation gives no value to this atom.
+    Unassigned(Atom),
+    /// The proposition contains a quantifier, which a valuation can't interpret.
+    Quantified,
+}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::Unassigned(atom) => write!(f, "no truth value for {}", atom),
+            EvaluationError::Quantified => write!(f, "quantified propositions have no truth value under a valuation"),
+        }
+    }
+}
+
+impl Error for EvaluationError {}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::valuation::{EvaluationError, Valuation};
+    use crate::proposition::{Atom, Proposition};
+
+    #[test]
+    fn test_evaluate() {
+        let proposition: Proposition = "(A > B) <> ~C".parse().unwrap();
+        let mut valuation: Valuation = [(Atom::prose("A"), true), (Atom::prose("B"), false)].into_iter().collect();
+        assert_eq!(proposition.evaluate(&valuation), Err(EvaluationError::Unassigned(Atom::prose("C"))));
+        valuation.set(Atom::prose("C"), true);
+        assert_eq!(proposition.evaluate(&valuation), Ok(true));
+        valuation.set(Atom::prose("C"), false);
+        assert_eq!(proposition.evaluate(&valuation), Ok(false));
+
+        let quantified: Proposition = "A & ∀<x> P(<x>)".parse().unwrap();
+        assert_eq!(quantified.evaluate(&valuation), Err(EvaluationError::Quantified));
+    }
+
+    #[test]
+    fn test_valuations() {
+        assert_eq!("A v B v A".parse::<Proposition>().unwrap().valuations().count(), 4);
+        assert_eq!(Proposition::Verum.valuations().collect::<Vec<Valuation>>(), vec![Valuation::new()]);
+    }
+
+    #[test]
+    fn test_tautologies() {
+        let excluded_middle: Proposition = "A v ~A".parse().unwrap();
+        assert_eq!(excluded_middle.is_tautology(), Ok(true));
+        let peirce: Proposition = "((A > B) > A) > A".parse().unwrap();
+        assert_eq!(peirce.is_tautology(), Ok(true));
+
+        let contingent: Proposition = "A > B".parse().unwrap();
+        assert_eq!(contingent.is_tautology(), Ok(false));
+        assert_eq!(contingent.is_satisfiable(), Ok(true));
+        assert_eq!(contingent.is_contradiction(), Ok(false));
+
+        let contradiction: Proposition = "A & ~A v ⊥".parse().unwrap();
+        assert_eq!(contradiction.is_contradiction(), Ok(true));
+        assert!("∃<x> P(<x>)".parse::<Proposition>().unwrap().is_tautology().is_err());
+    }
+}
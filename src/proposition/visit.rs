@@ -0,0 +1,147 @@
+use crate::proposition::atom::Atom;
+use crate::proposition::Proposition;
+
+/// Read-only traversal of a proposition. Each method's default carries on to everything
+/// beneath the proposition it is given, so implementations only override the methods for
+/// what they are interested in, calling `walk` from `visit` if they still want to descend.
+///
+/// ```
+/// use sequents::proposition::{Atom, Proposition, Visitor};
+///
+/// struct CountAtoms(usize);
+///
+/// impl Visitor<'_> for CountAtoms {
+///     fn visit_atom(&mut self, _atom: &Atom) { self.0 += 1 }
+/// }
+///
+/// let proposition: Proposition = "A & (B > A)".parse().unwrap();
+/// let mut counter = CountAtoms(0);
+/// counter.visit(&proposition);
+/// assert_eq!(counter.0, 3);
+/// ```
+pub trait Visitor<'a> {
+    /// Called on every subformula of a proposition, outermost first.
+    fn visit(&mut self, proposition: &'a Proposition) {
+        walk(self, proposition);
+    }
+
+    /// Called on every atom of a proposition, from left to right.
+    fn visit_atom(&mut self, _atom: &'a Atom) {}
+}
+
+/// Visit the content of proposition, or the atom itself if proposition is an atom.
+pub fn walk<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, proposition: &'a Proposition) {
+    match proposition {
+        Proposition::Atom(atom) => visitor.visit_atom(atom),
+        _ => for prop in proposition.content() { visitor.visit(prop) },
+    }
+}
+
+/// In-place traversal of a proposition, in the same order as `Visitor`.
+pub trait VisitorMut {
+    /// Called on every subformula of a proposition, outermost first.
+    fn visit_mut(&mut self, proposition: &mut Proposition) {
+        walk_mut(self, proposition);
+    }
+
+    /// Called on every atom of a proposition, from left to right.
+    fn visit_atom_mut(&mut self, _atom: &mut Atom) {}
+}
+
+/// Visit the content of proposition, or the atom itself if proposition is an atom.
+pub fn walk_mut<V: VisitorMut + ?Sized>(visitor: &mut V, proposition: &mut Proposition) {
+    match proposition {
+        Proposition::Atom(atom) => visitor.visit_atom_mut(atom),
+        _ => for prop in proposition.content_mut() { visitor.visit_mut(prop) },
+    }
+}
+
+/// A bottom-up computation over a proposition, passed to `Proposition::fold`.
+pub trait Fold {
+    type Output;
+
+    /// Combine proposition with the results already computed for its content. Atoms and
+    /// constants have no content, so content is empty for them.
+    fn fold(&mut self, proposition: &Proposition, content: Vec<Self::Output>) -> Self::Output;
+}
+
+impl Proposition {
+    /// Compute folder's result for self, starting from self's atoms and constants and
+    /// working outwards.
+    pub fn fold<F: Fold>(&self, folder: &mut F) -> F::Output {
+        let content: Vec<F::Output> = match self {
+            Proposition::Atom(_) => Vec::new(),
+            _ => self.content().into_iter().map(|prop| prop.fold(folder)).collect(),
+        };
+        folder.fold(self, content)
+    }
+
+    /// Return every atom in self, from left to right.
+    pub fn atoms(&self) -> Vec<&Atom> {
+        struct Atoms<'a>(Vec<&'a Atom>);
+        impl<'a> Visitor<'a> for Atoms<'a> {
+            fn visit_atom(&mut self, atom: &'a Atom) { self.0.push(atom) }
+        }
+
+        let mut atoms = Atoms(Vec::new());
+        atoms.visit(self);
+        atoms.0
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::visit::{walk, Fold, Visitor, VisitorMut};
+    use crate::proposition::{Atom, Predicate, Proposition};
+
+    /// Rename every predicate symbol.
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_atom_mut(&mut self, atom: &mut Atom) {
+            if let Predicate::Symbol(symbol) = &mut atom.predicate { symbol.push('\'') }
+        }
+    }
+
+    /// Count the quantifiers which bind a variable named `x`.
+    struct BindsX(usize);
+
+    impl Visitor<'_> for BindsX {
+        fn visit(&mut self, proposition: &Proposition) {
+            if let Proposition::Existential(var, _) | Proposition::Universal(var, _) = proposition {
+                if var == "x" { self.0 += 1 }
+            }
+            walk(self, proposition);
+        }
+    }
+
+    /// The number of leaves in a proposition's tree.
+    struct Leaves;
+
+    impl Fold for Leaves {
+        type Output = usize;
+
+        fn fold(&mut self, _proposition: &Proposition, content: Vec<usize>) -> usize {
+            if content.is_empty() { 1 } else { content.into_iter().sum() }
+        }
+    }
+
+    #[test]
+    fn test_visitors() {
+        let mut proposition: Proposition = "∀<x> P(<x>) & ∃<y> ∀<x> Q(<x>, <y>) > R(<y>) v ⊥".parse().unwrap();
+        let mut binds_x = BindsX(0);
+        binds_x.visit(&proposition);
+        assert_eq!(binds_x.0, 2);
+
+        Rename.visit_mut(&mut proposition);
+        assert_eq!(proposition.to_string(), "((∀<x>(P'(<x>)) & ∃<y>(∀<x>(Q'(<x>, <y>)))) > (R'(<y>) v ⊥))");
+        assert_eq!(proposition.atoms().len(), 3);
+    }
+
+    #[test]
+    fn test_fold() {
+        let proposition: Proposition = "~(A & B & ⊤) <> C".parse().unwrap();
+        assert_eq!(proposition.fold(&mut Leaves), 4);
+    }
+}
@@ -1,31 +1,243 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
 use crate::proposition::create::{proposition_from_string, PropositionCreationError};
-use crate::proposition::Proposition;
+use crate::proposition::lex::{tokenize, Token, TokenKind};
+use crate::proposition::{Proposition, Span};
 use crate::sequent::Sequent;
 
-fn from_string(s: String) -> Result<Sequent, SequentCreationError> {
-    let split_string: Vec<&str> = s.split("|~").collect();
-    if split_string.len() != 2 { return Err(SequentCreationError::IncorrectNumberOfTurnstiles)}
-    let antecedent = str_to_propositions(
-        split_string.get(0).expect("split_string has 2 items")
-    );
-    let consequent= str_to_propositions(
-        split_string.get(0).expect("split_string has 2 items")
-    );
-    if let (Ok(ant), Ok(con)) = (antecedent, consequent) {
-        return Ok( Sequent { ant, con } )
+pub(crate) const MATERIAL_TURNSTILES: &[&str] = &["|~"];
+pub(crate) const LOGICAL_TURNSTILES: &[&str] = &["|-", "⊢"];
+
+pub(crate) fn from_string(s: String) -> Result<Sequent, SequentCreationError> {
+    sequent_in(&s, 0, s.len()).map_err(|mut errors| errors.remove(0))
+}
+
+/// Parse each non-blank line of source as a sequent. Parsing continues past malformed
+/// lines and propositions so that every error is reported, each against the whole of source.
+pub(crate) fn sequents_from_lines(source: &str) -> Result<Vec<Sequent>, Vec<SequentCreationError>> {
+    let mut sequents: Vec<Sequent> = Vec::new();
+    let mut errors: Vec<SequentCreationError> = Vec::new();
+    let mut offset: usize = 0;
+    for line in source.split('\n') {
+        if !line.trim().is_empty() {
+            match sequent_in(source, offset, offset + line.len()) {
+                Ok(sequent) => sequents.push(sequent),
+                Err(mut line_errors) => errors.append(&mut line_errors),
+            }
+        }
+        offset += line.len() + 1;
     }
-    Err(SequentCreationError::ErrorConvertingPropositions)
+    if errors.is_empty() { Ok(sequents) } else { Err(errors) }
 }
 
-fn str_to_propositions(s: &str) -> Result<Vec<Proposition>, PropositionCreationError> {
-    let string: Vec<&str> = s.split(" ").collect();
-    string
+/// Parse the sequent in source[start..end], reporting every error against the whole of source.
+///
+/// ```text
+/// sequent := propositions turnstile propositions
+/// propositions := (proposition ("," proposition)*)?
+/// ```
+fn sequent_in(source: &str, start: usize, end: usize) -> Result<Sequent, Vec<SequentCreationError>> {
+    let tokens: Vec<Token> = tokenize(&source[start..end])
+        .map_err(|error| vec![SequentCreationError::ErrorConvertingPropositions(error.offset(source, start))])?
         .into_iter()
-        .map(|s| proposition_from_string(String::from(s)))
-        .collect()
+        .map(|token| Token { span: Span::new(token.span.start + start, token.span.end + start), ..token })
+        .collect();
+
+    let turnstiles: Vec<usize> = tokens.iter()
+        .enumerate()
+        .filter(|(_, token)| matches!(token.kind, TokenKind::Turnstile(_)))
+        .map(|(index, _)| index)
+        .collect();
+    match turnstiles.len() {
+        0 => return Err(vec![SequentCreationError::IncorrectNumberOfTurnstiles(Diagnostic::new(
+            source, Span::new(start, end), "missing turnstile", &["`|~`", "`|-`"]
+        ))]),
+        1 => {},
+        _ => return Err(vec![SequentCreationError::IncorrectNumberOfTurnstiles(Diagnostic::new(
+            source, tokens[turnstiles[1]].span, "extra turnstile", &["`,`", "a proposition"]
+        ))]),
+    }
+    let turnstile = &tokens[turnstiles[0]];
+    let turnstile_kind = match turnstile.kind {
+        TokenKind::Turnstile(kind) => kind,
+        _ => unreachable!("filtered to turnstiles"),
+    };
+
+    let ant = str_to_propositions(source, start, turnstile.span.start, &tokens[..turnstiles[0]]);
+    let con = str_to_propositions(source, turnstile.span.end, end, &tokens[turnstiles[0] + 1..]);
+    match (ant, con) {
+        (Ok(ant), Ok(con)) => Ok( Sequent { ant, con, turnstile: turnstile_kind } ),
+        (ant, con) => Err([ant.err(), con.err()].into_iter().flatten().flatten().collect()),
+    }
+}
+
+/// Parse the comma separated propositions in source[start..end], whose tokens are tokens,
+/// reporting errors against the whole of source. Only commas outside parentheses separate
+/// propositions.
+fn str_to_propositions(source: &str, start: usize, end: usize, tokens: &[Token]) -> Result<Vec<Proposition>, Vec<SequentCreationError>> {
+    if tokens.is_empty() { return Ok(Vec::new()) }
+
+    let mut bounds: Vec<(usize, usize)> = Vec::new();
+    let mut nestedness: usize = 0;
+    let mut proposition_start = start;
+    for token in tokens {
+        match token.kind {
+            TokenKind::LeftParen => nestedness += 1,
+            TokenKind::RightParen => nestedness = nestedness.saturating_sub(1),
+            TokenKind::Comma if nestedness == 0 => {
+                bounds.push((proposition_start, token.span.start));
+                proposition_start = token.span.end;
+            },
+            _ => {},
+        }
+    }
+    bounds.push((proposition_start, end));
+
+    let mut propositions: Vec<Proposition> = Vec::new();
+    let mut errors: Vec<SequentCreationError> = Vec::new();
+    for (start, end) in bounds {
+        match proposition_from_string(source[start..end].to_string()) {
+            Ok(proposition) => propositions.push(proposition),
+            Err(error) => errors.push(SequentCreationError::ErrorConvertingPropositions(error.offset(source, start))),
+        }
+    }
+    if errors.is_empty() { Ok(propositions) } else { Err(errors) }
 }
 
+#[derive(Debug, PartialEq, Clone)]
 pub enum SequentCreationError {
-    IncorrectNumberOfTurnstiles,
-    ErrorConvertingPropositions,
-}
\ No newline at end of file
+    IncorrectNumberOfTurnstiles(Diagnostic),
+    ErrorConvertingPropositions(PropositionCreationError),
+}
+
+impl SequentCreationError {
+    pub fn diagnostic(&self) -> &Diagnostic {
+        match self {
+            Self::IncorrectNumberOfTurnstiles(diagnostic) => diagnostic,
+            Self::ErrorConvertingPropositions(error) => error.diagnostic(),
+        }
+    }
+}
+
+impl Display for SequentCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
+
+impl Error for SequentCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::IncorrectNumberOfTurnstiles(_) => None,
+            Self::ErrorConvertingPropositions(error) => Some(error),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::create::PropositionCreationError;
+    use crate::proposition::{Atom, Proposition, Span};
+    use crate::sequent::create::{from_string, sequents_from_lines, SequentCreationError};
+    use crate::sequent::{Sequent, Turnstile};
+
+    #[test]
+    fn test_sequent_from_str() {
+        let sequent: Sequent = "A, the cat is on the mat |~ B & C, D".parse().unwrap();
+        let expected = Sequent::new(
+            vec![
+                Proposition::Atom(Atom::prose("A")),
+                Proposition::Atom(Atom::prose("the cat is on the mat")),
+            ],
+            vec![
+                Proposition::Conjunction(vec![
+                    Proposition::Atom(Atom::prose("B")),
+                    Proposition::Atom(Atom::prose("C"))
+                ]),
+                Proposition::Atom(Atom::prose("D")),
+            ],
+            Turnstile::Material
+        );
+        assert_eq!(sequent, expected);
+        assert_eq!(sequent.to_string(), "A, the cat is on the mat |~ (B & C), D");
+        assert!("A|~(B".parse::<Sequent>().is_err());
+    }
+
+    #[test]
+    fn test_empty_sides() {
+        let sequent: Sequent = "|~ A v ~A".parse().unwrap();
+        assert!(sequent.ant.is_empty());
+        assert_eq!(sequent.con.len(), 1);
+        assert_eq!(sequent.to_string(), "|~ (A v ~(A))");
+
+        let sequent: Sequent = "A |-".parse().unwrap();
+        assert_eq!(sequent.ant.len(), 1);
+        assert!(sequent.con.is_empty());
+
+        let sequent: Sequent = "⊢".parse().unwrap();
+        assert!(sequent.ant.is_empty() && sequent.con.is_empty());
+    }
+
+    #[test]
+    fn test_turnstiles() {
+        let material: Sequent = "A |~ A".parse().unwrap();
+        assert_eq!(material.turnstile(), Turnstile::Material);
+
+        let logical: Sequent = "A |- A".parse().unwrap();
+        assert_eq!(logical.turnstile(), Turnstile::Logical);
+        assert_eq!(logical.to_string(), "A |- A");
+
+        let logical: Sequent = "A ⊢ A".parse().unwrap();
+        assert_eq!(logical.turnstile(), Turnstile::Logical);
+        assert_ne!(material, logical);
+    }
+
+    #[test]
+    fn test_commas_in_quotes_and_parentheses() {
+        let sequent: Sequent = "\"A, B\" |~ C".parse().unwrap();
+        assert_eq!(sequent.ant, vec![Proposition::Atom(Atom::prose("A, B"))]);
+        assert_eq!(sequent.to_string(), "\"A, B\" |~ C");
+
+        let error = from_string("(A, B) |~ C".to_string()).unwrap_err();
+        assert_eq!(error.diagnostic().span, Span::new(2, 3));
+    }
+
+    #[test]
+    fn test_turnstile_errors() {
+        let error = from_string("A > B".to_string()).unwrap_err();
+        assert!(matches!(error, SequentCreationError::IncorrectNumberOfTurnstiles(_)));
+
+        let error = from_string("A |~ B |- C".to_string()).unwrap_err();
+        assert_eq!(error.diagnostic().span, Span::new(7, 9));
+    }
+
+    #[test]
+    fn test_proposition_errors_point_into_sequent() {
+        let error = from_string("A |~ (B".to_string()).unwrap_err();
+        match error {
+            SequentCreationError::ErrorConvertingPropositions(PropositionCreationError::UnclosedParenthesis(diagnostic)) => {
+                assert_eq!(diagnostic.source, "A |~ (B");
+                assert_eq!(diagnostic.span, Span::new(5, 6));
+            },
+            _ => panic!("expected an unclosed parenthesis"),
+        }
+
+        let error = from_string("A, |~ B".to_string()).unwrap_err();
+        assert!(matches!(error, SequentCreationError::ErrorConvertingPropositions(PropositionCreationError::EmptyString(_))));
+    }
+
+    #[test]
+    fn test_sequents_from_lines() {
+        let sequents = sequents_from_lines("A |~ B\n\nB |~ A").unwrap();
+        assert_eq!(sequents.len(), 2);
+
+        let errors = sequents_from_lines("(A, B & |~ B &\nA |~ B\nA").unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(errors[0], SequentCreationError::ErrorConvertingPropositions(PropositionCreationError::UnexpectedToken(_))));
+        assert!(matches!(errors[1], SequentCreationError::ErrorConvertingPropositions(PropositionCreationError::UnexpectedEnd(_))));
+        assert!(matches!(errors[2], SequentCreationError::IncorrectNumberOfTurnstiles(_)));
+        assert!(errors[2].to_string().contains("3 | A"));
+    }
+}
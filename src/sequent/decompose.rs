@@ -1,19 +1,24 @@
-use crate::proposition::Proposition;
-use crate::sequent::{Sequent, Side};
+use crate::proposition::{Arena, Atom, Node, PropId, Proposition, Term};
+use crate::sequent::{ground_terms, InternedSequent, Sequent, Side};
 use crate::{branch, leaf};
 
 
-pub fn decompose(mut sequent: Sequent, names: &Vec<String>) -> Option<Branch> {
+/// Return the ways sequent could have been derived by a single rule. Once sequent is atomic,
+/// the only rule left is the replacement of identicals, and axioms have no parents at all.
+pub fn decompose(mut sequent: Sequent, names: &[String]) -> Option<Branch> {
     match sequent.first_complex_proposition() {
-        None => None,
+        None => decompose_identities(sequent),
         Some(fcp) => {
             let proposition: Proposition = sequent.remove(&fcp);
             match proposition {
-                Proposition::Atom(_) => panic!("Atom should have been caught by previous match statement"),
+                Proposition::Atom(_) | Proposition::Verum | Proposition::Falsum => {
+                    panic!("Atoms and constants should have been caught by previous match statement")
+                },
                 Proposition::Negation(negatum) => Some(decompose_negation(sequent, fcp.side, *negatum, names)),
                 Proposition::Conditional(left, right) => Some(decompose_conditional(sequent, fcp.side, *left, *right, names)),
-                Proposition::Conjunction(left, right) => Some(decompose_conjunction(sequent, fcp.side, *left, *right, names)),
-                Proposition::Disjunction(left, right) => Some(decompose_disjunction(sequent, fcp.side, *left, *right, names)),
+                Proposition::Biconditional(left, right) => Some(decompose_biconditional(sequent, fcp.side, *left, *right, names)),
+                Proposition::Conjunction(conjuncts) => Some(decompose_conjunction(sequent, fcp.side, conjuncts, names)),
+                Proposition::Disjunction(disjuncts) => Some(decompose_disjunction(sequent, fcp.side, disjuncts, names)),
                 Proposition::Existential(var, content) => Some(decompose_existential(sequent, fcp.side, var, *content, names)),
                 Proposition::Universal(var, content) => Some(decompose_universal(sequent, fcp.side, var, *content, names)),
             }
@@ -21,7 +26,7 @@ pub fn decompose(mut sequent: Sequent, names: &Vec<String>) -> Option<Branch> {
     }
 }
 
-fn decompose_negation(mut sequent: Sequent, side: Side, negatum: Proposition, names: &Vec<String>) -> Branch {
+fn decompose_negation(mut sequent: Sequent, side: Side, negatum: Proposition, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
             sequent.push_right(negatum);
@@ -34,7 +39,7 @@ fn decompose_negation(mut sequent: Sequent, side: Side, negatum: Proposition, na
     }
 }
 
-fn decompose_conditional(mut sequent: Sequent, side: Side, left: Proposition, right: Proposition, names: &Vec<String>) -> Branch {
+fn decompose_conditional(mut sequent: Sequent, side: Side, left: Proposition, right: Proposition, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
             let mut parent_0: Sequent = sequent.clone();
@@ -51,55 +56,80 @@ fn decompose_conditional(mut sequent: Sequent, side: Side, left: Proposition, ri
     }
 }
 
-fn decompose_conjunction(mut sequent: Sequent, side: Side, left: Proposition, right: Proposition, names: &Vec<String>) -> Branch {
+fn decompose_biconditional(sequent: Sequent, side: Side, left: Proposition, right: Proposition, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
-            sequent.push_left(left);
-            sequent.push_left(right);
-            branch![leaf![sequent]]
+            let mut parent_0: Sequent = sequent.clone();
+            parent_0.push_left(left.clone());
+            parent_0.push_left(right.clone());
+            let mut parent_1: Sequent = sequent;
+            parent_1.push_right(left);
+            parent_1.push_right(right);
+            branch![leaf![parent_0, parent_1]]
         },
         Side::Consequent => {
             let mut parent_0: Sequent = sequent.clone();
-            parent_0.push_right(left);
+            parent_0.push_left(left.clone());
+            parent_0.push_right(right.clone());
             let mut parent_1: Sequent = sequent;
-            parent_1.push_right(right);
+            parent_1.push_left(right);
+            parent_1.push_right(left);
             branch![leaf![parent_0, parent_1]]
         }
     }
 }
 
-fn decompose_disjunction(mut sequent: Sequent, side: Side, left: Proposition, right: Proposition, names: &Vec<String>) -> Branch {
+/// Conjunctions are decomposed all at once: on the left into every conjunct, and on the
+/// right into a parent for each conjunct.
+fn decompose_conjunction(mut sequent: Sequent, side: Side, conjuncts: Vec<Proposition>, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
-            let mut parent_0: Sequent = sequent.clone();
-            parent_0.push_left(left);
-            let mut parent_1: Sequent = sequent;
-            parent_1.push_left(right);
-            branch![leaf![parent_0, parent_1]]
+            for conjunct in conjuncts { sequent.push_left(conjunct); }
+            branch![leaf![sequent]]
         },
         Side::Consequent => {
-            sequent.push_right(left);
-            sequent.push_right(right);
+            let mut parents: Vec<Sequent> = Vec::new();
+            for conjunct in conjuncts {
+                let mut parent: Sequent = sequent.clone();
+                parent.push_right(conjunct);
+                parents.push(parent);
+            }
+            Branch { leaves: vec![Leaf { parents }] }
+        }
+    }
+}
+
+/// Disjunctions are decomposed all at once: on the left into a parent for each disjunct,
+/// and on the right into every disjunct.
+fn decompose_disjunction(mut sequent: Sequent, side: Side, disjuncts: Vec<Proposition>, _names: &[String]) -> Branch {
+    match side {
+        Side::Antecedent => {
+            let mut parents: Vec<Sequent> = Vec::new();
+            for disjunct in disjuncts {
+                let mut parent: Sequent = sequent.clone();
+                parent.push_left(disjunct);
+                parents.push(parent);
+            }
+            Branch { leaves: vec![Leaf { parents }] }
+        },
+        Side::Consequent => {
+            for disjunct in disjuncts { sequent.push_right(disjunct); }
             branch![leaf![sequent]]
         }
     }
 }
 
-fn decompose_existential(sequent: Sequent, side: Side, var: String, content: Proposition, names: &Vec<String>) -> Branch {
+fn decompose_existential(sequent: Sequent, side: Side, var: String, content: Proposition, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
            todo!("A branch for each name not in the sequent, each branch has one leaf.")
         },
         Side::Consequent => {
-            let mut names = content.names();
-            for name in sequent.names() {
-                if !names.contains(&name) { names.push(name); }
-            }
             let mut leaves: Vec<Leaf> = Vec::new();
-            for name in names.into_iter() {
+            for term in instances(&sequent, &content) {
                 let mut leaf: Sequent = sequent.clone();
                 let mut prop: Proposition = content.clone();
-                prop.instantiate(&var, &name);
+                prop.substitute(&var, &term);
                 leaf.push_right(prop);
                 leaves.push(leaf![leaf])
             }
@@ -108,19 +138,15 @@ fn decompose_existential(sequent: Sequent, side: Side, var: String, content: Pro
     }
 }
 
-fn decompose_universal(sequent: Sequent, side: Side, var: String, content: Proposition, names: &Vec<String>) -> Branch {
+fn decompose_universal(sequent: Sequent, side: Side, var: String, content: Proposition, _names: &[String]) -> Branch {
     match side {
         Side::Antecedent => {
-            let mut names = content.names();
-            for name in sequent.names() {
-                if !names.contains(&name) { names.push(name); }
-            }
             let mut leaves: Vec<Leaf> = Vec::new();
-            for name in names.into_iter() {
+            for term in instances(&sequent, &content) {
                 let mut leaf: Sequent = sequent.clone();
                 let mut prop: Proposition = content.clone();
-                prop.instantiate(&var.clone(), &name);
-                leaf.push_right(prop);
+                prop.substitute(&var, &term);
+                leaf.push_left(prop);
                 leaves.push(leaf![leaf])
             }
             Branch { leaves }
@@ -131,37 +157,381 @@ fn decompose_universal(sequent: Sequent, side: Side, var: String, content: Propo
     }
 }
 
+/// Decompose an interned sequent in the same way as `decompose`, interning any new
+/// propositions into arena. Rules only rearrange handles and intern what is new, so they
+/// never copy or resolve a whole proposition.
+pub fn decompose_interned(sequent: &InternedSequent, arena: &mut Arena, _names: &[String]) -> Option<Branch<InternedSequent>> {
+    let fcp = match sequent.first_complex_proposition(arena) {
+        Some(fcp) => fcp,
+        None => return decompose_identities_interned(sequent, arena),
+    };
+    let mut rest = sequent.clone();
+    let id = rest.remove(&fcp);
+    let node = arena.node(id).clone();
+    // each parent is rest with some handles added to its antecedent and consequent
+    let parent = |ant: &[PropId], con: &[PropId]| {
+        let mut parent = rest.clone();
+        parent.ant.extend_from_slice(ant);
+        parent.con.extend_from_slice(con);
+        parent
+    };
+    let parents: Vec<InternedSequent> = match (node, fcp.side) {
+        (Node::Atom(_) | Node::Verum | Node::Falsum, _) => {
+            panic!("Atoms and constants should have been caught by previous match statement")
+        },
+        (Node::Negation(negatum), Side::Antecedent) => vec![parent(&[], &[negatum])],
+        (Node::Negation(negatum), Side::Consequent) => vec![parent(&[negatum], &[])],
+        (Node::Conditional(left, right), Side::Antecedent) => vec![parent(&[], &[left]), parent(&[right], &[])],
+        (Node::Conditional(left, right), Side::Consequent) => vec![parent(&[left], &[right])],
+        (Node::Biconditional(left, right), Side::Antecedent) => vec![parent(&[left, right], &[]), parent(&[], &[left, right])],
+        (Node::Biconditional(left, right), Side::Consequent) => vec![parent(&[left], &[right]), parent(&[right], &[left])],
+        (Node::Conjunction(conjuncts), Side::Antecedent) => vec![parent(&conjuncts, &[])],
+        (Node::Conjunction(conjuncts), Side::Consequent) => conjuncts.iter().map(|id| parent(&[], &[*id])).collect(),
+        (Node::Disjunction(disjuncts), Side::Antecedent) => disjuncts.iter().map(|id| parent(&[*id], &[])).collect(),
+        (Node::Disjunction(disjuncts), Side::Consequent) => vec![parent(&[], &disjuncts)],
+        // quantifiers have a leaf for each instance, rather than one leaf of several parents
+        (Node::Existential(var, content), Side::Consequent) | (Node::Universal(var, content), Side::Antecedent) => {
+            let terms = ground_terms(rest.atoms(arena).into_iter().chain(arena.atoms(content)));
+            let leaves = terms.iter()
+                .map(|term| {
+                    let instance = arena.substitute(content, &var, term);
+                    match fcp.side {
+                        Side::Antecedent => leaf![parent(&[instance], &[])],
+                        Side::Consequent => leaf![parent(&[], &[instance])],
+                    }
+                })
+                .collect();
+            return Some(Branch { leaves })
+        },
+        (Node::Existential(_, _) | Node::Universal(_, _), _) => {
+            todo!("A branch for each name not in the sequent, each branch has one leaf.")
+        },
+    };
+    Some(Branch { leaves: vec![Leaf { parents }] })
+}
+
+/// Apply the replacement rule: from `s = t` in the antecedent, an atom containing s (or t)
+/// may be added alongside the atom with one occurrence of s replaced by t (or vice versa).
+/// There is a leaf for each new atom. Only atoms whose arguments already occur in sequent
+/// are added, so that eg. `<a> = f(<a>)` can't build ever deeper terms, and there are only
+/// finitely many atoms to add. Returns None for axioms and when nothing new can be added.
+fn decompose_identities(sequent: Sequent) -> Option<Branch> {
+    if sequent.is_axiom() { return None }
+
+    let atoms = |side: Side| -> Vec<&Atom> {
+        sequent.side(&side).iter()
+            .filter_map(|prop| match prop {
+                Proposition::Atom(atom) => Some(atom),
+                _ => None,
+            })
+            .collect()
+    };
+    let mut leaves: Vec<Leaf> = Vec::new();
+    for (side, atom) in replacements(&atoms(Side::Antecedent), &atoms(Side::Consequent)) {
+        let prop = Proposition::Atom(atom);
+        if sequent.side(&side).contains(&prop) { continue }
+        let mut leaf: Sequent = sequent.clone();
+        match side {
+            Side::Antecedent => leaf.push_left(prop),
+            Side::Consequent => leaf.push_right(prop),
+        }
+        if !leaves.iter().any(|existing| existing.parents[0] == leaf) { leaves.push(leaf![leaf]) }
+    }
+    if leaves.is_empty() { None } else { Some(Branch { leaves }) }
+}
+
+/// Apply the replacement rule to an interned sequent, as `decompose_identities` does.
+fn decompose_identities_interned(sequent: &InternedSequent, arena: &mut Arena) -> Option<Branch<InternedSequent>> {
+    if sequent.is_axiom(arena) { return None }
+
+    let atoms = |ids: &[PropId]| -> Vec<&Atom> {
+        ids.iter()
+            .filter_map(|id| match arena.node(*id) {
+                Node::Atom(atom) => Some(atom),
+                _ => None,
+            })
+            .collect()
+    };
+    let replaced = replacements(&atoms(&sequent.ant), &atoms(&sequent.con));
+    let mut leaves: Vec<Leaf<InternedSequent>> = Vec::new();
+    for (side, atom) in replaced {
+        let id = arena.insert(Node::Atom(atom));
+        if sequent.side(&side).contains(&id) { continue }
+        let mut leaf: InternedSequent = sequent.clone();
+        match side {
+            Side::Antecedent => leaf.ant.push(id),
+            Side::Consequent => leaf.con.push(id),
+        }
+        if !leaves.iter().any(|existing| existing.parents[0] == leaf) { leaves.push(leaf![leaf]) }
+    }
+    if leaves.is_empty() { None } else { Some(Branch { leaves }) }
+}
+
+/// Return the atoms the replacement rule makes from a sequent with the atoms ant in its
+/// antecedent and con in its consequent, each with the side it goes on, in order and
+/// possibly repeated. Those with an argument not already in the sequent are left out.
+fn replacements(ant: &[&Atom], con: &[&Atom]) -> Vec<(Side, Atom)> {
+    let identities: Vec<(&Term, &Term)> = ant.iter()
+        .filter_map(|atom| atom.identity())
+        .filter(|(left, right)| left != right)
+        .collect();
+    let terms: Vec<&Term> = ant.iter().chain(con.iter()).flat_map(|atom| atom.subterms()).collect();
+
+    let mut replaced: Vec<(Side, Atom)> = Vec::new();
+    for (left, right) in identities {
+        for (side, atoms) in [(Side::Antecedent, ant), (Side::Consequent, con)] {
+            for atom in atoms {
+                let new = atom.replacements(left, right).into_iter().chain(atom.replacements(right, left));
+                replaced.extend(new.filter(|atom| atom.args.iter().all(|arg| terms.contains(&arg))).map(|atom| (side, atom)));
+            }
+        }
+    }
+    replaced
+}
+
+/// Return the ground terms a quantifier over content may be instantiated with: those built
+/// from the names and function symbols in content and the rest of sequent.
+fn instances(sequent: &Sequent, content: &Proposition) -> Vec<Term> {
+    ground_terms(sequent.propositions().chain(std::iter::once(content)).flat_map(|prop| prop.atoms()))
+}
+
 
 /// Leaves represent one way a sequent could have been constructed. For invertible rules,
 /// there is only the one set of parents. For non-invertible rules there may be multiple
 /// sets of parents.
-pub struct Leaf { parents: Vec<Sequent> }
+pub struct Leaf<S = Sequent> { pub parents: Vec<S> }
 
 /// Branches represent the full set of ways a sequent could have been constructed.
-pub struct Branch { leaves: Vec<Leaf> }
+pub struct Branch<S = Sequent> { pub leaves: Vec<Leaf<S>> }
 
 #[macro_export]
 macro_rules! branch {
     ( $( $x:expr ),* ) => {
-        {
-            let mut leaves = Vec::new();
-            $(
-                leaves.push($x);
-            )*
-            Branch { leaves }
-        }
+        Branch { leaves: vec![$( $x ),*] }
     };
 }
 
 #[macro_export]
 macro_rules! leaf {
     ( $( $x:expr ),* ) => {
-        {
-            let mut parents = Vec::new();
-            $(
-                parents.push($x);
-            )*
-            Leaf { parents }
+        Leaf { parents: vec![$( $x ),*] }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::Arena;
+    use crate::sequent::decompose::{decompose, decompose_interned};
+    use crate::sequent::Sequent;
+
+    fn parents(sequent: &str) -> Vec<Vec<String>> {
+        let sequent: Sequent = sequent.parse().unwrap();
+        decompose(sequent, &[])
+            .unwrap()
+            .leaves
+            .into_iter()
+            .map(|leaf| leaf.parents.iter().map(|parent| parent.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_decompose_negation() {
+        assert_eq!(parents("~A |~ B"), vec![vec!["|~ B, A"]]);
+        assert_eq!(parents("A |~ ~B"), vec![vec!["A, B |~"]]);
+    }
+
+    #[test]
+    fn test_decompose_n_ary_conjunctions_and_disjunctions() {
+        assert_eq!(parents("A & B & C |~ D"), vec![vec!["A, B, C |~ D"]]);
+        assert_eq!(parents("|~ A & B & C"), vec![vec!["|~ A", "|~ B", "|~ C"]]);
+        assert_eq!(parents("A v B v C |~"), vec![vec!["A |~", "B |~", "C |~"]]);
+        assert_eq!(parents("D |~ A v (B v C)"), vec![vec!["D |~ A, (B v C)"]]);
+    }
+
+    #[test]
+    fn test_decompose_biconditional() {
+        assert_eq!(parents("A <> B |~ C"), vec![vec!["A, B |~ C", "|~ C, A, B"]]);
+        assert_eq!(parents("|~ A iff B"), vec![vec!["A |~ B", "B |~ A"]]);
+    }
+
+    #[test]
+    fn test_decompose_universal_respects_rebinding() {
+        assert_eq!(
+            parents("∀<x>(Cat(<x>) & ∃<x> Dog(<x>)) |~ Cat(<kitty>)"),
+            vec![vec!["(Cat(<kitty>) & ∃<x>(Dog(<x>))) |~ Cat(<kitty>)"]]
+        );
+    }
+
+    #[test]
+    fn test_decompose_universal_into_nested_terms() {
+        assert_eq!(
+            parents("∀<x> Cat(<x>) |~ Cat(mother(<kitty>))"),
+            vec![
+                vec!["Cat(mother(<kitty>)) |~ Cat(mother(<kitty>))"],
+                vec!["Cat(<kitty>) |~ Cat(mother(<kitty>))"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decompose_existential_with_function_instances() {
+        assert_eq!(
+            parents("Loves(<kitty>, <rex>) |~ ∃<x> Loves(<x>, f(<x>))"),
+            vec![
+                vec!["Loves(<kitty>, <rex>) |~ Loves(<kitty>, f(<kitty>))"],
+                vec!["Loves(<kitty>, <rex>) |~ Loves(<rex>, f(<rex>))"],
+                vec!["Loves(<kitty>, <rex>) |~ Loves(f(<kitty>), f(f(<kitty>)))"],
+                vec!["Loves(<kitty>, <rex>) |~ Loves(f(<rex>), f(f(<rex>)))"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reflexive_identities_are_axioms() {
+        let sequent: Sequent = "|~ <a> = <a>".parse().unwrap();
+        assert!(sequent.is_axiom());
+        assert!(decompose(sequent, &[]).is_none());
+
+        let sequent: Sequent = "<a> = <a> |~ P(<a>)".parse().unwrap();
+        assert!(!sequent.is_axiom());
+        assert!(decompose(sequent, &[]).is_none());
+    }
+
+    #[test]
+    fn test_constants_are_axioms() {
+        for source in ["⊥ |~ A", "A |~ ⊤", "false, A |- B", "|~ true"] {
+            let sequent: Sequent = source.parse().unwrap();
+            assert!(sequent.is_axiom());
+            assert!(decompose(sequent, &[]).is_none());
+        }
+        assert!(!"⊤ |~ ⊥".parse::<Sequent>().unwrap().is_axiom());
+        assert!("∀<x> P(<x>) |~ ∀<y> P(<y>)".parse::<Sequent>().unwrap().is_axiom());
+        let x: Sequent = "∀<x> P(<x>) |~ A".parse().unwrap();
+        let y: Sequent = "∀<y> P(<y>) |~ A".parse().unwrap();
+        assert_eq!(x.canonical(), y.canonical());
+        assert_eq!(parents("|~ ~⊥"), vec![vec!["⊥ |~"]]);
+    }
+
+    #[test]
+    fn test_decompose_identity_replacement() {
+        assert_eq!(
+            parents("<a> = <b>, P(<a>) |~ P(<b>)"),
+            vec![
+                vec!["<a> = <b>, P(<a>), <b> = <b> |~ P(<b>)"],
+                vec!["<a> = <b>, P(<a>), <a> = <a> |~ P(<b>)"],
+                vec!["<a> = <b>, P(<a>), P(<b>) |~ P(<b>)"],
+                vec!["<a> = <b>, P(<a>) |~ P(<b>), P(<a>)"],
+            ]
+        );
+
+        // symmetry: replacing <a> in the reflexive <a> = <a> gives an axiom
+        let symmetry = parents("<a> = <b> |~ <b> = <a>");
+        assert!(symmetry.contains(&vec!["<a> = <b> |~ <b> = <a>, <b> = <b>".to_string()]));
+        let axiom: Sequent = "<a> = <b> |~ <b> = <a>, <b> = <b>".parse().unwrap();
+        assert!(axiom.is_axiom());
+    }
+
+    #[test]
+    fn test_identity_replacement_terminates() {
+        // replacing <a> with f(<a>) over and over would never end
+        assert_eq!(
+            parents("<a> = f(<a>), P(<a>) |~ Q"),
+            vec![
+                vec!["<a> = f(<a>), P(<a>), f(<a>) = f(<a>) |~ Q"],
+                vec!["<a> = f(<a>), P(<a>), <a> = <a> |~ Q"],
+                vec!["<a> = f(<a>), P(<a>), P(f(<a>)) |~ Q"],
+            ]
+        );
+        assert!(!derivable("<a> = f(<a>), P(<a>) |~ Q".parse().unwrap()));
+        // deeper terms can still be reached when the sequent mentions them
+        assert!(derivable("<a> = f(<a>), P(<a>) |~ P(f(f(<a>)))".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_decompose_interned_matches_decompose() {
+        let sources = [
+            "~A |~ B",
+            "A > B |~ A <> B",
+            "A <> B, C & D & E |~",
+            "|~ A & B & C, D v E",
+            "∀<x> Cat(<x>) |~ Cat(mother(<kitty>))",
+            "∀<x>(Cat(<x>) & ∃<x> Dog(<x>)) |~ Cat(<kitty>)",
+            "A |~ ∃<x> (Cat(<x>) v ∀<y> Loves(<x>, <y>)), Dog(<rex>)",
+            "<a> = <b>, P(<a>) |~ P(<b>)",
+            "<a> = f(<a>), P(<a>) |~ Q",
+            "A |~ A",
+            "⊥ |~ A",
+            "|~ <a> = <a>",
+        ];
+        for source in sources {
+            let mut arena = Arena::new();
+            let interned = source.parse::<Sequent>().unwrap().intern(&mut arena);
+            let resolved: Option<Vec<Vec<String>>> = decompose_interned(&interned, &mut arena, &[]).map(|branch| {
+                branch.leaves
+                    .into_iter()
+                    .map(|leaf| leaf.parents.iter().map(|parent| parent.resolve(&arena).to_string()).collect())
+                    .collect()
+            });
+            let expected: Option<Vec<Vec<String>>> = decompose(source.parse().unwrap(), &[]).map(|_| parents(source));
+            assert_eq!(resolved, expected);
+        }
+    }
+
+    #[test]
+    fn test_interned_axioms() {
+        let mut arena = Arena::new();
+        for source in ["A, B |~ C, B", "⊥ |~ A", "A |~ ⊤", "|~ <a> = <a>", "A |~ B", "⊤ |~ ⊥", "<a> = <b> |~"] {
+            let sequent: Sequent = source.parse().unwrap();
+            assert_eq!(sequent.intern(&mut arena).is_axiom(&arena), sequent.is_axiom(), "{source}");
+        }
+        // handles are only shared by identical propositions, not alphabetic variants
+        let sequent: Sequent = "∀<x> P(<x>) |~ ∀<y> P(<y>)".parse().unwrap();
+        assert!(!sequent.intern(&mut arena).is_axiom(&arena));
+        assert!(sequent.canonical().intern(&mut arena).is_axiom(&arena));
+    }
+
+    #[test]
+    fn test_interned_sequents_share_propositions() {
+        let mut arena = Arena::new();
+        let sequent: Sequent = "A & B |~ (A & B) v C".parse().unwrap();
+        let interned = sequent.intern(&mut arena);
+        assert_eq!(interned.resolve(&arena), sequent);
+        // A, B, (A & B), C and the disjunction
+        assert_eq!(arena.len(), 5);
+        assert_eq!(sequent.intern(&mut arena), interned);
+    }
+
+    /// Whether sequent is derivable from axioms by decomposing it exhaustively.
+    fn derivable(sequent: Sequent) -> bool {
+        sequent.is_axiom() || decompose(sequent, &[]).is_some_and(|branch| {
+            branch.leaves.into_iter().any(|leaf| leaf.parents.into_iter().all(derivable))
+        })
+    }
+
+    #[test]
+    fn test_decompose_agrees_with_truth_tables() {
+        let sources = [
+            "A |~ A",
+            "A |~ B",
+            "A > B, A |~ B",
+            "A > B, B |~ A",
+            "|~ ((A > B) > A) > A",
+            "|~ A v ~A",
+            "A <> B |~ (A > B) & (B > A)",
+            "A v B, ~A |~ B",
+            "A & B & C |~ C v D",
+            "~(A & B) |~ ~A v ~B",
+            "⊤ > A |~ A",
+            "A |~ ⊥",
+            "A <> ~A |~",
+            "|~ (A <> B) <> (B <> A)",
+            "|~ A & B > C",
+        ];
+        for source in sources {
+            let sequent: Sequent = source.parse().unwrap();
+            let tautology = sequent.to_proposition().is_tautology().unwrap();
+            assert_eq!(derivable(sequent), tautology, "{source}");
         }
     }
 }
@@ -1,21 +1,72 @@
-mod decompose;
-mod create;
+pub mod decompose;
+pub(crate) mod create;
+mod occurrence;
 
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use itertools::Itertools;
-use crate::proposition::Proposition;
+use crate::proposition::{Arena, Atom, Measure, ModelError, Node, Path, Polarity, PropId, Proposition, Structure, Term};
+use crate::sequent::create::{from_string, sequents_from_lines};
 
+pub use crate::sequent::create::SequentCreationError;
+pub use crate::sequent::occurrence::Occurrence;
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Sequent {
     ant: Vec<Proposition>,
     con: Vec<Proposition>,
+    turnstile: Turnstile,
+}
+
+/// The consequence relation a sequent asserts between its antecedent and consequent.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub enum Turnstile {
+    /// Material consequence, written `|~`.
+    Material,
+    /// Logical consequence, written `|-` or `⊢`.
+    Logical,
+}
+
+impl Display for Turnstile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Turnstile::Material => write!(f, "|~"),
+            Turnstile::Logical => write!(f, "|-"),
+        }
+    }
 }
 
 impl Sequent {
-    /// Return the number of connectives in self.
+    pub fn new(ant: Vec<Proposition>, con: Vec<Proposition>, turnstile: Turnstile) -> Sequent {
+        Sequent { ant, con, turnstile }
+    }
+
+    /// Return the consequence relation self asserts.
+    pub fn turnstile(&self) -> Turnstile {
+        self.turnstile
+    }
+
+    /// Return the depth of the most deeply nested proposition in self's antecedent plus that
+    /// of the most deeply nested in its consequent. See `measure` for other ways of
+    /// measuring complexity.
     pub fn complexity(&self) -> usize {
         let ant_complexity = itertools::max(self.ant.iter().map(|prop| prop.complexity())).unwrap_or(0);
         let con_complexity = itertools::max(self.con.iter().map(|prop| prop.complexity())).unwrap_or(0);
-        return ant_complexity + con_complexity
+        ant_complexity + con_complexity
+    }
+
+    /// Return the complexity of self according to measure. Connectives and atoms are
+    /// totalled over all the propositions in self, depth and quantifier rank are the
+    /// greatest of any proposition, and alternation depth is that of the proposition self
+    /// asserts, so that quantifiers in the antecedent count as their duals.
+    pub fn measure(&self, measure: Measure) -> usize {
+        match measure {
+            Measure::Connectives | Measure::Atoms => self.propositions().map(|prop| prop.measure(measure)).sum(),
+            Measure::Depth | Measure::QuantifierRank => {
+                self.propositions().map(|prop| prop.measure(measure)).max().unwrap_or(0)
+            },
+            Measure::AlternationDepth => self.to_proposition().alternation_depth(),
+        }
     }
 
     /// Returns the proposition at index of side.
@@ -45,14 +96,109 @@ impl Sequent {
         None
     }
 
+    /// Whether self is an axiom: some proposition appears on both sides (up to the renaming
+    /// of bound variables), the antecedent contains `⊥`, or the consequent contains `⊤` or a
+    /// reflexive identity like `<a> = <a>`.
+    pub fn is_axiom(&self) -> bool {
+        let shared = self.ant.iter().any(|left| self.con.iter().any(|right| left.alpha_equivalent(right)));
+        let falsum = self.ant.contains(&Proposition::Falsum);
+        let verum = self.con.contains(&Proposition::Verum);
+        let reflexive = self.con.iter().any(|prop| match prop {
+            Proposition::Atom(atom) => atom.identity().is_some_and(|(left, right)| left == right),
+            _ => false,
+        });
+        shared || falsum || verum || reflexive
+    }
+
+    /// Return the propositions on side of self.
+    pub fn side(&self, side: &Side) -> &[Proposition] {
+        match side {
+            Side::Antecedent => &self.ant,
+            Side::Consequent => &self.con,
+        }
+    }
+
+    fn side_mut(&mut self, side: &Side) -> &mut Vec<Proposition> {
+        match side {
+            Side::Antecedent => &mut self.ant,
+            Side::Consequent => &mut self.con,
+        }
+    }
+
+    /// Return every subformula of every proposition in self, along with the coordinates of
+    /// the proposition it is in, its path within that proposition and its polarity in self.
+    /// Propositions in the antecedent are negative, and those in the consequent positive.
+    pub fn subformulas(&self) -> impl Iterator<Item = (Coordinates, Path, Polarity, &Proposition)> {
+        let sides = [(Side::Antecedent, Polarity::Negative), (Side::Consequent, Polarity::Positive)];
+        sides.into_iter().flat_map(move |(side, polarity)| {
+            self.side(&side).iter().enumerate().flat_map(move |(index, prop)| {
+                prop.subformulas_with(polarity).map(move |(path, polarity, subformula)| {
+                    (Coordinates { side, index }, path, polarity, subformula)
+                })
+            })
+        })
+    }
+
+    /// Return the subformula at path in the proposition at coordinates in self, if there is
+    /// one.
+    pub fn get(&self, coordinates: &Coordinates, path: &Path) -> Option<&Proposition> {
+        self.side(&coordinates.side).get(coordinates.index)?.get(path)
+    }
+
+    /// Put new at path in the proposition at coordinates in self, returning the subformula
+    /// it replaces, or return None and leave self unchanged if there is no such subformula.
+    pub fn replace_at(&mut self, coordinates: &Coordinates, path: &Path, new: Proposition) -> Option<Proposition> {
+        self.side_mut(&coordinates.side).get_mut(coordinates.index)?.replace_at(path, new)
+    }
+
+    /// Return a copy of self with every proposition in its canonical form, so that sequents
+    /// which differ only in the names of bound variables compare equal.
+    pub fn canonical(&self) -> Sequent {
+        Sequent {
+            ant: self.ant.iter().map(|prop| prop.canonical()).collect(),
+            con: self.con.iter().map(|prop| prop.canonical()).collect(),
+            turnstile: self.turnstile,
+        }
+    }
+
+    /// Return the proposition self asserts: the conjunction of its antecedent implies the
+    /// disjunction of its consequent. An empty antecedent is `⊤` and an empty consequent `⊥`.
+    pub fn to_proposition(&self) -> Proposition {
+        let join = |props: &[Proposition], connective: fn(Vec<Proposition>) -> Proposition, empty: Proposition| match props {
+            [] => empty,
+            [prop] => prop.clone(),
+            _ => connective(props.to_vec()),
+        };
+        Proposition::Conditional(
+            Box::new(join(&self.ant, Proposition::Conjunction, Proposition::Verum)),
+            Box::new(join(&self.con, Proposition::Disjunction, Proposition::Falsum)),
+        )
+    }
+
+    /// Whether structure satisfies self: if it makes every proposition in the antecedent
+    /// true, it makes some proposition in the consequent true.
+    pub fn is_satisfied_by(&self, structure: &Structure) -> Result<bool, ModelError> {
+        self.to_proposition().is_satisfied_by(structure)
+    }
+
     /// Push proposition to the consequent of self.
     pub(crate) fn push_right(&mut self, proposition: Proposition) {
-        self.ant.push(proposition);
+        self.con.push(proposition);
     }
 
     /// Push proposition to the antecedent of self.
     pub(crate) fn push_left(&mut self, proposition: Proposition) {
-        self.con.push(proposition);
+        self.ant.push(proposition);
+    }
+
+    /// Return the propositions in self's antecedent followed by those in its consequent.
+    pub fn propositions(&self) -> impl Iterator<Item = &Proposition> {
+        self.ant.iter().chain(self.con.iter())
+    }
+
+    /// Return the ground terms quantifiers in self may be instantiated with.
+    pub fn ground_terms(&self) -> Vec<Term> {
+        ground_terms(self.propositions().flat_map(|prop| prop.atoms()))
     }
 
     /// Return the names in all the propositions in self.
@@ -70,13 +216,133 @@ impl Sequent {
         }
         names
     }
+
+    /// Intern every proposition in self into arena.
+    pub fn intern(&self, arena: &mut Arena) -> InternedSequent {
+        InternedSequent {
+            ant: self.ant.iter().map(|prop| arena.intern(prop)).collect(),
+            con: self.con.iter().map(|prop| arena.intern(prop)).collect(),
+            turnstile: self.turnstile,
+        }
+    }
+
+    /// Parse each non-blank line of s as a sequent. Unlike `from_str`, this does not stop at
+    /// the first error, and reports every malformed line and proposition.
+    pub fn from_lines(s: &str) -> Result<Vec<Sequent>, Vec<SequentCreationError>> {
+        sequents_from_lines(s)
+    }
+}
+
+/// A sequent whose propositions are handles into an `Arena`. Cloning one copies only the
+/// handles, and comparing or hashing one never looks at the propositions themselves.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+pub struct InternedSequent {
+    pub ant: Vec<PropId>,
+    pub con: Vec<PropId>,
+    pub turnstile: Turnstile,
+}
+
+impl InternedSequent {
+    /// Rebuild the sequent self stands for.
+    pub fn resolve(&self, arena: &Arena) -> Sequent {
+        Sequent {
+            ant: self.ant.iter().map(|id| arena.resolve(*id)).collect(),
+            con: self.con.iter().map(|id| arena.resolve(*id)).collect(),
+            turnstile: self.turnstile,
+        }
+    }
+
+    /// Whether self is an axiom, as `Sequent::is_axiom` decides, except that a proposition
+    /// on both sides must have the same handle, so must be identical rather than just equal
+    /// up to the renaming of bound variables. The two agree once self is atomic.
+    pub fn is_axiom(&self, arena: &Arena) -> bool {
+        let shared = self.ant.iter().any(|id| self.con.contains(id));
+        let falsum = self.ant.iter().any(|id| *arena.node(*id) == Node::Falsum);
+        let verum = self.con.iter().any(|id| *arena.node(*id) == Node::Verum);
+        let reflexive = self.con.iter().any(|id| match arena.node(*id) {
+            Node::Atom(atom) => atom.identity().is_some_and(|(left, right)| left == right),
+            _ => false,
+        });
+        shared || falsum || verum || reflexive
+    }
+
+    /// Return the handles on side of self.
+    pub fn side(&self, side: &Side) -> &[PropId] {
+        match side {
+            Side::Antecedent => &self.ant,
+            Side::Consequent => &self.con,
+        }
+    }
+
+    /// Return the atoms in the propositions on both sides of self, without resolving them.
+    pub fn atoms<'a>(&self, arena: &'a Arena) -> Vec<&'a Atom> {
+        self.ant.iter().chain(self.con.iter()).flat_map(|id| arena.atoms(*id)).collect()
+    }
+
+    /// Returns the coordinates of self's first (from left to right) complex proposition.
+    /// Returns None if self is atomic.
+    pub fn first_complex_proposition(&self, arena: &Arena) -> Option<Coordinates> {
+        if let Some(index) = self.ant.iter().position(|id| !arena.node(*id).is_atomic()) {
+            return Some(Coordinates { side: Side::Antecedent, index })
+        }
+        self.con.iter()
+            .position(|id| !arena.node(*id).is_atomic())
+            .map(|index| Coordinates { side: Side::Consequent, index })
+    }
+
+    /// Returns the proposition at index of side.
+    ///
+    /// # Panics
+    /// Panics if index is greater than self.side's length.
+    pub fn remove(&mut self, coordinates: &Coordinates) -> PropId {
+        match coordinates.side {
+            Side::Antecedent => self.ant.remove(coordinates.index),
+            Side::Consequent => self.con.remove(coordinates.index)
+        }
+    }
+}
+
+/// Return every ground term occurring in atoms, followed by each function symbol in atoms
+/// applied once to every combination of the names in atoms.
+pub(crate) fn ground_terms<'a>(atoms: impl Iterator<Item = &'a Atom>) -> Vec<Term> {
+    let atoms: Vec<&Atom> = atoms.collect();
+    let mut terms: Vec<Term> = Vec::new();
+    for term in atoms.iter().flat_map(|atom| atom.subterms()) {
+        if term.is_ground() && !terms.contains(term) { terms.push(term.clone()); }
+    }
+
+    let names: Vec<Term> = atoms.iter()
+        .flat_map(|atom| atom.names())
+        .unique()
+        .map(Term::Name)
+        .collect();
+    let functions: Vec<(String, usize)> = atoms.iter()
+        .flat_map(|atom| atom.functions())
+        .unique()
+        .collect();
+    for (symbol, arity) in functions.into_iter().filter(|(_, arity)| *arity > 0) {
+        for args in itertools::repeat_n(names.iter().cloned(), arity).multi_cartesian_product() {
+            let term = Term::Function(symbol.clone(), args);
+            if !terms.contains(&term) { terms.push(term); }
+        }
+    }
+    terms
+}
+
+impl FromStr for Sequent {
+    type Err = SequentCreationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_string(String::from(s))
+    }
 }
 
 impl Display for Sequent {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let ant: String = self.ant.iter().map(|x| x.to_string()).join(", ");
         let con: String = self.con.iter().map(|x| x.to_string()).join(", ");
-        write!(f, "{ant} |~ {con}")
+        // trim so that empty sides don't leave stray spaces
+        write!(f, "{}", format!("{ant} {} {con}", self.turnstile).trim())
     }
 }
 
@@ -84,16 +350,47 @@ impl Clone for Sequent {
     fn clone(&self) -> Self {
         let ant = self.ant.clone();
         let con = self.con.clone();
-        Sequent { ant, con }
+        Sequent { ant, con, turnstile: self.turnstile }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Side {
     Antecedent,
     Consequent
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Coordinates {
     pub side: Side,
     pub index: usize
 }
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::Proposition;
+    use crate::sequent::decompose::decompose;
+    use crate::sequent::Sequent;
+
+    #[test]
+    fn test_push_sides() {
+        let mut sequent: Sequent = "A |~ B".parse().unwrap();
+        sequent.push_left("C".parse::<Proposition>().unwrap());
+        sequent.push_right("D".parse::<Proposition>().unwrap());
+        assert_eq!(sequent.to_string(), "A, C |~ B, D");
+    }
+
+    #[test]
+    fn test_universal_instances_go_to_the_antecedent() {
+        let sequent: Sequent = "∀<x> Cat(<x>) |~ Cat(<kitty>)".parse().unwrap();
+        let parents: Vec<String> = decompose(sequent, &[])
+            .unwrap()
+            .leaves
+            .into_iter()
+            .flat_map(|leaf| leaf.parents)
+            .map(|parent| parent.to_string())
+            .collect();
+        assert_eq!(parents, vec!["Cat(<kitty>) |~ Cat(<kitty>)"]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use itertools::Itertools;
+use crate::proposition::path::combine_all;
+use crate::proposition::{Atom, Path, Polarity, Proposition};
+use crate::sequent::{Coordinates, Sequent};
+
+/// One place a subformula occurs in a sequent: the proposition it is in, where it is within
+/// that proposition, and its polarity in the sequent.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Occurrence {
+    pub coordinates: Coordinates,
+    pub path: Path,
+    pub polarity: Polarity,
+}
+
+impl Sequent {
+    /// Return every occurrence of proposition as a subformula of self, in the order of
+    /// `subformulas`.
+    pub fn occurrences(&self, proposition: &Proposition) -> Vec<Occurrence> {
+        self.subformulas()
+            .filter(|(_, _, _, subformula)| *subformula == proposition)
+            .map(|(coordinates, path, polarity, _)| Occurrence { coordinates, path, polarity })
+            .collect()
+    }
+
+    /// Return the polarity of proposition in self, combining all its occurrences, or None
+    /// if it doesn't occur in self.
+    pub fn polarity_of(&self, proposition: &Proposition) -> Option<Polarity> {
+        self.occurrences(proposition).into_iter().map(|occurrence| occurrence.polarity).reduce(Polarity::combine)
+    }
+
+    /// Return each distinct subformula of self with the combined polarity of all its
+    /// occurrences. A subformula is positive in self when making it true can only help
+    /// self hold, and negative when making it false can only help.
+    pub fn polarities(&self) -> HashMap<&Proposition, Polarity> {
+        combine_all(self.subformulas().map(|(_, _, polarity, prop)| (prop, polarity)))
+    }
+
+    /// Return each distinct atom in self with the combined polarity of all its occurrences.
+    pub fn atom_polarities(&self) -> HashMap<&Atom, Polarity> {
+        combine_all(self.subformulas().filter_map(|(_, _, polarity, prop)| match prop {
+            Proposition::Atom(atom) => Some((atom, polarity)),
+            _ => None,
+        }))
+    }
+
+    /// Return the atoms which occur in self with only one polarity, in the order they first
+    /// occur. Each of them can only help self hold by being true, or only by being false.
+    pub fn pure_atoms(&self) -> Vec<&Atom> {
+        let polarities = self.atom_polarities();
+        self.propositions()
+            .flat_map(|prop| prop.atoms())
+            .unique()
+            .filter(|atom| polarities[atom] != Polarity::Both)
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::proposition::{Atom, Path, Polarity, Proposition};
+    use crate::sequent::{Coordinates, Occurrence, Sequent, Side};
+
+    fn prop(s: &str) -> Proposition {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let sequent: Sequent = "A > B, ~A |~ B v ~C".parse().unwrap();
+        assert_eq!(sequent.occurrences(&prop("A")), vec![
+            Occurrence { coordinates: Coordinates { side: Side::Antecedent, index: 0 }, path: Path::from(vec![0]), polarity: Polarity::Positive },
+            Occurrence { coordinates: Coordinates { side: Side::Antecedent, index: 1 }, path: Path::from(vec![0]), polarity: Polarity::Positive },
+        ]);
+        assert_eq!(sequent.polarity_of(&prop("A")), Some(Polarity::Positive));
+        assert_eq!(sequent.polarity_of(&prop("B")), Some(Polarity::Both));
+        assert_eq!(sequent.polarity_of(&prop("C")), Some(Polarity::Negative));
+        assert_eq!(sequent.polarity_of(&prop("~A")), Some(Polarity::Negative));
+        assert_eq!(sequent.polarity_of(&prop("D")), None);
+    }
+
+    #[test]
+    fn test_polarities() {
+        let sequent: Sequent = "∀<x>(Cat(<x>) > Mammal(<x>)), A <> B |~ Mammal(<kitty>)".parse().unwrap();
+        let polarities = sequent.polarities();
+        assert_eq!(polarities[&prop("Cat(<x>)")], Polarity::Positive);
+        assert_eq!(polarities[&prop("Mammal(<x>)")], Polarity::Negative);
+        assert_eq!(polarities[&prop("A <> B")], Polarity::Negative);
+
+        let atoms = sequent.atom_polarities();
+        assert_eq!(atoms[&Atom::prose("A")], Polarity::Both);
+        assert_eq!(atoms.len(), 5);
+    }
+
+    #[test]
+    fn test_pure_atoms() {
+        let sequent: Sequent = "A > B, B > C, D |~ A > C, ~(D & E)".parse().unwrap();
+        let pure: Vec<String> = sequent.pure_atoms().iter().map(|atom| atom.to_string()).collect();
+        assert_eq!(pure, vec!["D", "E"]);
+    }
+}